@@ -1,15 +1,12 @@
+use aws_config::SdkConfig;
 use color_eyre::Result;
-use std::io::stdout;
+use std::{io::stdout, time::Duration};
 
 use crossterm::event::{Event as CrosstermEvent, EventStream, KeyCode, KeyEvent};
 use futures::{FutureExt, StreamExt};
 use ratatui::{
     backend::{Backend, CrosstermBackend},
-    crossterm::{
-        event::KeyEventKind,
-        terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-        ExecutableCommand,
-    },
+    crossterm::event::KeyEventKind,
     layout::{Constraint, Layout, Rect},
     Frame, Terminal,
 };
@@ -19,31 +16,46 @@ use tokio::{
         mpsc::{self, UnboundedReceiver, UnboundedSender},
     },
     task::JoinHandle,
+    time::{self, Instant, MissedTickBehavior},
 };
 
 use crate::{
-    components::{clusters::Clusters, context::Context, Component, Event},
+    components::{
+        clusters::Clusters, command::CommandPrompt, container_instances::ContainerInstances,
+        containers::Containers, context::Context, context_switcher::ContextSwitcher,
+        deployments::Deployments, describe::Describe, events::Events, history::History,
+        login_prompt::LoginPrompt, logs::Logs, services::Services,
+        task_definitions::TaskDefinitions, tasks::Tasks, toast::Toast, Component, Event,
+    },
+    error::{AwsError, ErrorKind},
+    exec::{session_manager_plugin, ExecSession, SESSION_MANAGER_PLUGIN},
+    options::AppOptions,
+    route::Route,
     state_store::{action::Action, State, StateStore},
     termination::{create_termination, Interrupted},
+    tui::{install_hooks, Crossterm, TerminalGuard, TerminalMode},
     ui::{KeybindingsWidget, LogoWidget},
 };
 
-pub async fn run_app() -> Result<()> {
-    stdout().execute(EnterAlternateScreen)?;
-    enable_raw_mode()?;
-    let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
-    terminal.clear()?;
+pub async fn run_app(config: SdkConfig, options: AppOptions) -> Result<()> {
+    install_hooks(Crossterm)?;
 
-    let config = aws_config::load_from_env().await;
+    let mut guard = TerminalGuard::new(CrosstermBackend::new(stdout()), Crossterm)?;
+    guard.terminal().clear()?;
 
     let (terminator, mut interrupt_rx) = create_termination();
-    let (state_store, state_rx) = StateStore::new(config);
-    let (mut app, action_rx) = App::new(state_rx);
+    let (mut state_store, state_rx) = StateStore::new(config, options.clone());
+    let (mut app, action_rx) = App::new(state_rx, options);
 
-    tokio::try_join!(
+    let result = tokio::try_join!(
         state_store.event_loop(terminator, action_rx, interrupt_rx.resubscribe()),
-        app.run(&mut terminal, interrupt_rx.resubscribe()),
-    )?;
+        app.run(guard.terminal(), interrupt_rx.resubscribe()),
+    );
+
+    // restore the terminal before printing so the message, or the report
+    // of a failure, stays visible
+    drop(guard);
+    result?;
 
     if let Ok(reason) = interrupt_rx.recv().await {
         match reason {
@@ -54,25 +66,44 @@ pub async fn run_app() -> Result<()> {
         println!("exited because of an unexpected error");
     }
 
-    stdout().execute(LeaveAlternateScreen)?;
-    disable_raw_mode()?;
-
     Ok(())
 }
 
+struct Screen {
+    route: Route,
+    component: Box<dyn Component + Send>,
+}
+
 pub struct App {
     task: JoinHandle<()>,
     event_tx: UnboundedSender<Event>,
     event_rx: UnboundedReceiver<Event>,
     action_tx: UnboundedSender<Action>,
     state_rx: UnboundedReceiver<State>,
+    state: State,
 
     context_component: Context,
-    cluster_component: Clusters,
+    command_prompt: CommandPrompt,
+    context_switcher: ContextSwitcher,
+    login_prompt: LoginPrompt,
+    toast: Toast,
+    screens: Vec<Screen>,
+
+    options: AppOptions,
+    paused: bool,
+    // session the plugin last ran for, the state keeps it until the store
+    // handled `FinishExec`
+    exec_session_id: Option<String>,
+    // a refresh was sent and the store has not answered yet, ticks are
+    // skipped meanwhile so slow listings do not pile up
+    refresh_pending: bool,
 }
 
 impl App {
-    pub fn new(state_rx: UnboundedReceiver<State>) -> (Self, UnboundedReceiver<Action>) {
+    pub fn new(
+        state_rx: UnboundedReceiver<State>,
+        options: AppOptions,
+    ) -> (Self, UnboundedReceiver<Action>) {
         let (event_tx, event_rx) = mpsc::unbounded_channel();
         let (action_tx, action_rx) = mpsc::unbounded_channel();
 
@@ -83,8 +114,17 @@ impl App {
                 event_rx,
                 action_tx,
                 state_rx,
+                state: State::default(),
                 context_component: Context::default(),
-                cluster_component: Clusters::new(),
+                command_prompt: CommandPrompt::default(),
+                context_switcher: ContextSwitcher::default(),
+                login_prompt: LoginPrompt::default(),
+                toast: Toast::default(),
+                screens: vec![],
+                options,
+                paused: false,
+                exec_session_id: None,
+                refresh_pending: false,
             },
             action_rx,
         )
@@ -95,34 +135,86 @@ impl App {
         terminal: &mut Terminal<impl Backend>,
         mut interrupt_rx: broadcast::Receiver<Interrupted>,
     ) -> Result<Interrupted> {
-        self.task = tokio::spawn(Self::event_loop(self.event_tx.clone()));
+        self.task = tokio::spawn(Self::event_loop(
+            self.event_tx.clone(),
+            self.options.refresh_interval,
+        ));
+        self.update_refresh();
 
         self.context_component
             .register_action_handler(self.action_tx.clone())?;
-        self.cluster_component
-            .register_action_handler(self.action_tx.clone())?;
-
         self.context_component.init()?;
-        self.cluster_component.init()?;
+
+        self.push(Route::Clusters)?;
 
         let result: Result<Interrupted> = loop {
             tokio::select! {
                 Some(event) = self.event_rx.recv() => {
+                    // a sign-in asked for by the store comes first
+                    if self.login_prompt.is_active() {
+                        let action = self.login_prompt.handle_events(Some(event.clone()));
+                        self.dispatch(action)?;
+                        self.draw(terminal)?;
+                        continue;
+                    }
+                    // the prompt captures every key while it is open
+                    if self.command_prompt.is_active() {
+                        let action = self.command_prompt.handle_events(Some(event.clone()));
+                        self.dispatch(action)?;
+                        self.draw(terminal)?;
+                        continue;
+                    }
+                    // so does the context switcher
+                    if self.context_switcher.is_active() {
+                        let action = self.context_switcher.handle_events(Some(event.clone()));
+                        self.dispatch(action)?;
+                        self.draw(terminal)?;
+                        continue;
+                    }
+
                     let action_tx = self.action_tx.clone();
 
+                    let captured = match (&event, self.screens.last()) {
+                        (Event::Key(key), Some(screen)) => screen.component.captures_key(key),
+                        _ => false,
+                    };
+
+                    let mut handled = false;
                     match event {
                         Event::Quit => action_tx.send(Action::Quit)?,
-                        Event::Key(key) => self.handle_key_event(key)?,
+                        Event::Tick if !self.paused => self.refresh()?,
+                        Event::Key(key) if !captured => handled = self.handle_key_event(key)?,
                         _ => {}
                     };
 
                     self.context_component.handle_events(Some(event.clone()));
-                    self.cluster_component.handle_events(Some(event.clone()));
+                    // a key that went back is not also a key of the screen
+                    // below, an Esc would clear its filter
+                    if let Some(screen) = self.screens.last_mut().filter(|_| !handled) {
+                        let action = screen.component.handle_events(Some(event.clone()));
+                        // rebuild the props so filters typed into the screen apply immediately
+                        screen.component.set_state(&self.state);
+                        self.dispatch(action)?;
+                    }
                 },
                 Some(state) = self.state_rx.recv() => {
                     //println!("{state:?}");
+                    self.refresh_pending = false;
                     self.context_component.set_state(&state);
-                    self.cluster_component.set_state(&state);
+                    self.context_switcher.set_state(&state);
+                    self.login_prompt.set_state(&state);
+                    self.toast.set_state(&state);
+                    for screen in self.screens.iter_mut() {
+                        screen.component.set_state(&state);
+                    }
+                    self.state = state;
+
+                    if let Some(session) = self.state.exec_session.clone() {
+                        if self.exec_session_id.as_ref() != Some(&session.session_id) {
+                            self.exec_session_id = Some(session.session_id.clone());
+                            self.exec(terminal, session).await?;
+                        }
+                    }
                 },
                 Ok(interrupted) = interrupt_rx.recv() => {
                     break Ok(interrupted);
@@ -137,11 +229,26 @@ impl App {
         result
     }
 
-    async fn event_loop(event_tx: UnboundedSender<Event>) {
+    // terminal events, plus a tick every refresh interval
+    async fn event_loop(event_tx: UnboundedSender<Event>, tick_rate: Option<Duration>) {
         let mut event_stream = EventStream::new();
+        let mut ticker = tick_rate.map(|period| {
+            let mut ticker = time::interval_at(Instant::now() + period, period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            ticker
+        });
 
         loop {
+            let tick = async {
+                match ticker.as_mut() {
+                    Some(ticker) => {
+                        ticker.tick().await;
+                    }
+                    None => std::future::pending().await,
+                }
+            };
             let event = tokio::select! {
+                _ = tick => Event::Tick,
                 crossterm_event = event_stream.next().fuse() => match crossterm_event {
                     Some(Ok(event)) => match event {
                         CrosstermEvent::Key(key) if key.kind == KeyEventKind::Press => Event::Key(key),
@@ -162,21 +269,241 @@ impl App {
         }
     }
 
+    // hands the terminal to the session manager plugin, or the built-in
+    // client when it is not installed, until the shell exits, the event loop
+    // is stopped meanwhile so it does not read the keys typed into the shell
+    async fn exec(
+        &mut self,
+        terminal: &mut Terminal<impl Backend>,
+        session: ExecSession,
+    ) -> Result<()> {
+        self.task.abort();
+        let _ = (&mut self.task).await;
+        terminal.show_cursor()?;
+        Crossterm.leave()?;
+
+        println!(
+            "opening a shell in {}, exit it to return to raws",
+            session.container_name
+        );
+        let result = match session_manager_plugin() {
+            Some(plugin) => {
+                let mut command = tokio::process::Command::from(
+                    session.plugin_command(plugin, self.state.profile.as_deref()),
+                );
+                match command.status().await {
+                    Ok(status) if status.success() => Ok(()),
+                    Ok(status) => Err(format!("{SESSION_MANAGER_PLUGIN} exited with {status}")),
+                    Err(error) => Err(format!("{SESSION_MANAGER_PLUGIN} could not run, {error}")),
+                }
+            }
+            #[cfg(unix)]
+            None => libssm::run_in_terminal(&session.stream_url, &session.token_value)
+                .await
+                .map(|_| ())
+                .map_err(|error| error.to_string()),
+            #[cfg(not(unix))]
+            None => Err(format!("{SESSION_MANAGER_PLUGIN} is not on the PATH")),
+        };
+
+        Crossterm.enter()?;
+        terminal.clear()?;
+        self.task = tokio::spawn(Self::event_loop(
+            self.event_tx.clone(),
+            self.options.refresh_interval,
+        ));
+
+        self.action_tx.send(Action::FinishExec {
+            container_name: session.container_name,
+            result: result.map_err(|message| AwsError {
+                operation: "StartSession".to_string(),
+                kind: ErrorKind::Other,
+                message,
+            }),
+        })?;
+        Ok(())
+    }
+
     fn draw(&mut self, terminal: &mut Terminal<impl Backend>) -> Result<()> {
         terminal.draw(|frame| self.render_frame(frame))?;
         Ok(())
     }
 
-    fn handle_key_event(&mut self, key: KeyEvent) -> Result<()> {
+    // true when the key was used up, it is then not passed on to the screen
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<bool> {
         let action_tx = self.action_tx.clone();
 
-        if key.kind == KeyEventKind::Press && key.code == KeyCode::Char('q') {
-            action_tx.send(Action::Quit)?;
+        if key.kind != KeyEventKind::Press {
+            return Ok(false);
+        }
+
+        match key.code {
+            KeyCode::Char('q') => action_tx.send(Action::Quit)?,
+            KeyCode::Esc if self.toast.is_visible() => {
+                action_tx.send(Action::DismissToast)?;
+                return Ok(true);
+            }
+            KeyCode::Esc => return self.pop(),
+            KeyCode::Char(':') => {
+                let route = self.screens.last().map(|screen| &screen.route);
+                self.command_prompt.open(
+                    route.and_then(Route::cluster_arn).map(str::to_string),
+                    route.and_then(Route::service_name).map(str::to_string),
+                );
+            }
+            KeyCode::Char('p') => self.dispatch(Action::OpenContextSwitcher)?,
+            KeyCode::Char('R') => {
+                self.refresh_pending = false;
+                self.refresh()?;
+            }
+            KeyCode::Char('a') if self.options.refresh_interval.is_some() => {
+                self.paused = !self.paused;
+                self.update_refresh();
+            }
+            _ => {}
+        }
+
+        Ok(false)
+    }
+
+    // handle navigation requested by the active screen, everything else
+    // is forwarded to the state store
+    fn dispatch(&mut self, action: Action) -> Result<()> {
+        match action {
+            Action::Noop => {}
+            Action::Navigate(route) => self.push(route)?,
+            Action::OpenContextSwitcher => {
+                self.context_switcher.open();
+                self.action_tx.send(Action::GetProfiles)?;
+            }
+            // screens below the clusters belong to the previous account
+            Action::SwitchContext { .. } => {
+                self.truncate(1)?;
+                self.action_tx.send(action)?;
+            }
+            Action::Switch(route) => {
+                self.truncate(1)?;
+                if route != Route::Clusters {
+                    self.push(route)?;
+                }
+                self.update_breadcrumb();
+            }
+            action => self.action_tx.send(action)?,
+        }
+
+        Ok(())
+    }
+
+    // only the visible screen is polled again
+    fn refresh(&mut self) -> Result<()> {
+        if self.refresh_pending {
+            return Ok(());
+        }
+        if let Some(screen) = self.screens.last() {
+            self.action_tx.send(Action::Refresh(screen.route.clone()))?;
+            self.refresh_pending = true;
+        }
+        Ok(())
+    }
+
+    fn update_refresh(&mut self) {
+        let refresh = match (self.options.refresh_interval, self.paused) {
+            (None, _) => "off".to_string(),
+            (Some(_), true) => "paused".to_string(),
+            (Some(interval), false) => format!("every {}s", interval.as_secs()),
+        };
+        self.context_component.set_refresh(refresh);
+    }
+
+    fn push(&mut self, route: Route) -> Result<()> {
+        let mut component: Box<dyn Component + Send> = match route.clone() {
+            Route::Clusters => Box::new(Clusters::new()),
+            Route::Services { cluster_arn } => {
+                Box::new(Services::new(cluster_arn, self.options.scale_confirm_above))
+            }
+            Route::Tasks {
+                cluster_arn,
+                service_name,
+            } => Box::new(Tasks::new(cluster_arn, service_name)),
+            Route::Containers {
+                cluster_arn,
+                task_arn,
+            } => Box::new(Containers::new(cluster_arn, task_arn)),
+            Route::Deployments {
+                cluster_arn,
+                service_arn,
+            } => Box::new(Deployments::new(cluster_arn, service_arn)),
+            Route::History {
+                cluster_arn,
+                service_arn,
+            } => Box::new(History::new(cluster_arn, service_arn)),
+            Route::Events {
+                cluster_arn,
+                service_arn,
+            } => Box::new(Events::new(cluster_arn, service_arn)),
+            Route::Logs {
+                cluster_arn,
+                task_arn,
+                container_name,
+            } => Box::new(Logs::new(cluster_arn, task_arn, container_name)),
+            Route::TaskDefinitions { cluster_arn } => {
+                Box::new(TaskDefinitions::new(cluster_arn.clone()))
+            }
+            Route::ContainerInstances { cluster_arn } => {
+                Box::new(ContainerInstances::new(cluster_arn))
+            }
+            Route::Describe { target } => Box::new(Describe::new(target)),
+        };
+
+        component.register_action_handler(self.action_tx.clone())?;
+        component.init()?;
+        component.set_state(&self.state);
+
+        self.screens.push(Screen { route, component });
+        self.update_breadcrumb();
+
+        Ok(())
+    }
+
+    // the root screen is never popped
+    fn pop(&mut self) -> Result<bool> {
+        if self.screens.len() <= 1 {
+            return Ok(false);
         }
+        self.truncate(self.screens.len() - 1)?;
+        Ok(true)
+    }
 
+    // the events of a container are only kept while its logs screen is
+    // open, reopening it reads the stream again
+    fn truncate(&mut self, len: usize) -> Result<()> {
+        for screen in self.screens.drain(len.min(self.screens.len())..) {
+            if let Route::Logs {
+                task_arn,
+                container_name,
+                ..
+            } = screen.route
+            {
+                self.action_tx.send(Action::CloseLogs {
+                    task_arn,
+                    container_name,
+                })?;
+            }
+        }
+        self.update_breadcrumb();
         Ok(())
     }
 
+    fn update_breadcrumb(&mut self) {
+        let breadcrumb = self
+            .screens
+            .iter()
+            .filter_map(|screen| screen.route.breadcrumb())
+            .collect::<Vec<String>>();
+
+        self.context_component.set_breadcrumb(breadcrumb);
+    }
+
     fn render_frame(&mut self, frame: &mut Frame) {
         let [context, content] =
             Layout::vertical([Constraint::Percentage(20), Constraint::Percentage(80)])
@@ -201,6 +528,32 @@ impl App {
     }
 
     fn draw_content_block(&mut self, frame: &mut Frame, area: Rect) {
-        self.cluster_component.draw(frame, area);
+        let area = if self.command_prompt.is_active() {
+            let [prompt_area, area] =
+                Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(area);
+            self.command_prompt.draw(frame, prompt_area);
+            area
+        } else {
+            area
+        };
+
+        if let Some(screen) = self.screens.last_mut() {
+            screen.component.draw(frame, area);
+        }
+
+        if self.context_switcher.is_active() {
+            let switcher_area = self.context_switcher.area(area);
+            self.context_switcher.draw(frame, switcher_area);
+        }
+
+        if self.login_prompt.is_active() {
+            let prompt_area = self.login_prompt.area(area);
+            self.login_prompt.draw(frame, prompt_area);
+        }
+
+        if self.toast.is_visible() {
+            let toast_area = self.toast.area(area);
+            self.toast.draw(frame, toast_area);
+        }
     }
 }
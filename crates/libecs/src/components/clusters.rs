@@ -1,42 +1,103 @@
+use std::cmp::Ordering;
+
 use color_eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
 use ratatui::{
-    layout::{Alignment, Constraint, Rect},
+    layout::{Alignment, Constraint, Layout, Rect},
     prelude::Stylize,
     style::{palette::tailwind, Modifier, Style},
-    text::Text,
-    widgets::{Block, Cell, Row, Table, TableState},
+    text::{Line, Span},
+    widgets::{Block, Cell, Paragraph, Row, Table, TableState},
     Frame,
 };
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::state_store::{action::Action, ClusterItem, State};
+use crate::{
+    error::AwsError,
+    route::{DescribeTarget, Route},
+    state_store::{action::Action, ClusterItem, Listing, State},
+};
+
+use super::{
+    filter::Filter,
+    table::{clamp_selection, loading_footer, select_next, select_previous, Sort},
+    Component,
+};
+
+const HEADERS: [&str; 11] = [
+    "NAME",
+    "REGION",
+    "ACCOUNT",
+    "STATUS",
+    "SERVICES",
+    "RUNNING",
+    "PENDING",
+    "INSTANCES",
+    "INSIGHTS",
+    "CAPACITY PROVIDERS",
+    "EXEC LOGGING",
+];
 
-use super::Component;
+fn compare(column: usize, a: &ClusterItem, b: &ClusterItem) -> Ordering {
+    match column {
+        1 => a.region.cmp(&b.region),
+        2 => a.account.cmp(&b.account),
+        3 => a.status.cmp(&b.status),
+        4 => a.active_services_count.cmp(&b.active_services_count),
+        5 => a.running_tasks_count.cmp(&b.running_tasks_count),
+        6 => a.pending_tasks_count.cmp(&b.pending_tasks_count),
+        7 => a
+            .registered_container_instances_count
+            .cmp(&b.registered_container_instances_count),
+        8 => a.container_insights.cmp(&b.container_insights),
+        9 => a
+            .capacity_provider_strategy
+            .cmp(&b.capacity_provider_strategy),
+        10 => a.execute_command_logging.cmp(&b.execute_command_logging),
+        _ => a.name.cmp(&b.name),
+    }
+}
 
 #[derive(Default)]
 struct Props {
     clusters: Vec<ClusterItem>,
+    failures: Vec<(String, AwsError)>,
+    loading: bool,
 }
 
-impl From<&State> for Props {
-    fn from(state: &State) -> Self {
-        let clusters = state
-            .cluster_arns
-            .clone()
+impl Props {
+    fn from_state(state: &State, filter: &Filter, sort: &Sort) -> Self {
+        let mut clusters = state
+            .clusters
             .iter()
-            .map(|arn| ClusterItem::from(arn.clone()))
+            .filter(|cluster| {
+                let mut fields = vec![
+                    cluster.name.as_str(),
+                    cluster.region.as_str(),
+                    cluster.account.as_str(),
+                    cluster.status.as_str(),
+                ];
+                fields.extend(cluster.tags.iter().map(String::as_str));
+                filter.matches(&fields)
+            })
+            .cloned()
             .collect::<Vec<ClusterItem>>();
 
-        //clusters.sort_by(|room_a, room_b| room_a.name.cmp(&room_b.name));
+        clusters.sort_by(|a, b| sort.apply(compare(sort.column(), a, b)));
 
-        Self { clusters }
+        Self {
+            clusters,
+            failures: state.cluster_failures.clone(),
+            loading: state.loading.contains(&Listing::Clusters),
+        }
     }
 }
 
 pub struct Clusters {
     action_tx: Option<UnboundedSender<Action>>,
     props: Props,
+    filter: Filter,
+    sort: Sort,
     table_state: TableState,
 }
 
@@ -45,36 +106,43 @@ impl Clusters {
         Self {
             action_tx: None,
             props: Props::default(),
+            filter: Filter::default(),
+            sort: Sort::default(),
             table_state: TableState::default().with_selected(None),
         }
     }
 
-    fn next(&mut self) {
-        let i = match self.table_state.selected() {
-            Some(i) => {
-                if i >= self.props.clusters.len() - 1 {
-                    i
-                } else {
-                    i + 1
-                }
-            }
-            None => 0,
-        };
-        self.table_state.select(Some(i))
-    }
+    fn draw_failures(&self, frame: &mut Frame, rect: Rect) {
+        let width = self
+            .props
+            .failures
+            .iter()
+            .map(|(scope, _)| scope.len())
+            .max()
+            .unwrap_or_default();
+        let lines = self
+            .props
+            .failures
+            .iter()
+            .map(|(scope, error)| {
+                Line::from(vec![
+                    Span::from(format!("{scope:<width$}  ")).bold(),
+                    Span::from(format!("{}: ", error.kind.title())),
+                    Span::from(error.message.clone()),
+                    Span::from(format!(" ({})", error.kind.hint())).dark_gray(),
+                ])
+            })
+            .collect::<Vec<Line>>();
 
-    fn previous(&mut self) {
-        let i = match self.table_state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    i
-                } else {
-                    i - 1
-                }
-            }
-            None => self.props.clusters.len() - 1,
-        };
-        self.table_state.select(Some(i))
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::bordered()
+                    .title(format!(" Failed listings[{}] ", self.props.failures.len()))
+                    .title_alignment(Alignment::Center)
+                    .style(Style::default().fg(tailwind::RED.c400)),
+            ),
+            rect,
+        );
     }
 }
 
@@ -91,8 +159,13 @@ impl Component for Clusters {
         Ok(())
     }
 
+    fn captures_key(&self, key: &KeyEvent) -> bool {
+        self.filter.captures_key(key)
+    }
+
     fn set_state(&mut self, state: &State) {
-        self.props = Props::from(state);
+        self.props = Props::from_state(state, &self.filter, &self.sort);
+        clamp_selection(&mut self.table_state, self.props.clusters.len());
     }
 
     fn update(&mut self, action: Action) -> Action {
@@ -105,46 +178,130 @@ impl Component for Clusters {
             return Action::Noop;
         }
 
+        if self.filter.handle_key_event(key) {
+            return Action::Noop;
+        }
+
         match key.code {
             KeyCode::Char('k') => {
-                self.previous();
+                select_previous(&mut self.table_state, self.props.clusters.len());
                 Action::Noop
             }
             KeyCode::Char('j') => {
-                self.next();
+                select_next(&mut self.table_state, self.props.clusters.len());
+                Action::Noop
+            }
+            KeyCode::Char('s') => {
+                self.sort.next_column(HEADERS.len());
+                Action::Noop
+            }
+            KeyCode::Char('S') => {
+                self.sort.reverse();
                 Action::Noop
             }
+            KeyCode::Enter => match self.table_state.selected() {
+                Some(i) => Action::Navigate(Route::Services {
+                    cluster_arn: self.props.clusters[i].arn.clone(),
+                }),
+                None => Action::Noop,
+            },
+            KeyCode::Char('t') => match self.table_state.selected() {
+                Some(i) => Action::Navigate(Route::Tasks {
+                    cluster_arn: self.props.clusters[i].arn.clone(),
+                    service_name: None,
+                }),
+                None => Action::Noop,
+            },
+            KeyCode::Char('d') => match self.table_state.selected() {
+                Some(i) => Action::Navigate(Route::Describe {
+                    target: DescribeTarget::Cluster {
+                        cluster_arn: self.props.clusters[i].arn.clone(),
+                    },
+                }),
+                None => Action::Noop,
+            },
             _ => Action::Noop,
         }
     }
 
     fn draw(&mut self, frame: &mut Frame, rect: Rect) {
-        //println!("{:?}", self.props.clusters);
+        // regions that could not be listed are reported below the table
+        let rect = if self.props.failures.is_empty() {
+            rect
+        } else {
+            let [rect, failures_area] = Layout::vertical([
+                Constraint::Min(0),
+                Constraint::Length(self.props.failures.len() as u16 + 2),
+            ])
+            .areas(rect);
+            self.draw_failures(frame, failures_area);
+            rect
+        };
+
         let selected_style = Style::default()
             .add_modifier(Modifier::REVERSED)
             .fg(tailwind::CYAN.c100);
 
-        let header = ["NAME"].into_iter().map(Cell::from).collect::<Row>().style(
-            Style::default()
-                .bold()
-                .bg(tailwind::CYAN.c200)
-                .fg(tailwind::BLACK),
-        );
+        let header = HEADERS
+            .iter()
+            .enumerate()
+            .map(|(column, title)| Cell::from(self.sort.header(column, title)))
+            .collect::<Row>()
+            .style(
+                Style::default()
+                    .bold()
+                    .bg(tailwind::CYAN.c200)
+                    .fg(tailwind::BLACK),
+            );
         let rows = self.props.clusters.iter().map(|data| {
-            let item = [&data.name];
+            let item = [
+                data.name.clone(),
+                data.region.clone(),
+                data.account.clone(),
+                data.status.clone(),
+                data.active_services_count.to_string(),
+                data.running_tasks_count.to_string(),
+                data.pending_tasks_count.to_string(),
+                data.registered_container_instances_count.to_string(),
+                data.container_insights.clone(),
+                data.capacity_provider_strategy.clone(),
+                data.execute_command_logging.clone(),
+            ];
             item.into_iter()
-                .map(|content| Cell::from(Text::from(content.clone())))
+                .map(|content| Cell::from(self.filter.highlight(content)))
                 .collect::<Row>()
         });
 
-        let t = Table::new(rows, [Constraint::Min(0)])
-            .header(header)
-            .highlight_style(selected_style);
+        let mut t = Table::new(
+            rows,
+            [
+                Constraint::Min(20),
+                Constraint::Length(14),
+                Constraint::Length(16),
+                Constraint::Length(10),
+                Constraint::Length(9),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(10),
+                Constraint::Length(9),
+                Constraint::Min(20),
+                Constraint::Length(13),
+            ],
+        )
+        .header(header)
+        .highlight_style(selected_style);
+        if self.props.loading {
+            t = t.footer(loading_footer(self.props.clusters.len()));
+        }
 
         frame.render_stateful_widget(
             t.block(
                 Block::bordered()
-                    .title(format!(" Clusters[{}] ", self.props.clusters.len()))
+                    .title(format!(
+                        " Clusters[{}] {}",
+                        self.props.clusters.len(),
+                        self.filter.title()
+                    ))
                     .title_alignment(Alignment::Center)
                     .style(Style::default().bold().fg(tailwind::CYAN.c200)),
             ),
@@ -153,3 +310,84 @@ impl Component for Clusters {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_ecs::types::Cluster;
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+
+    fn state() -> State {
+        let cluster = |name: &str, region: &str, running: i32| {
+            ClusterItem::from(
+                &Cluster::builder()
+                    .cluster_arn(format!("arn:aws:ecs:{region}:123456789012:cluster/{name}"))
+                    .cluster_name(name)
+                    .status("ACTIVE")
+                    .running_tasks_count(running)
+                    .build(),
+            )
+        };
+        State {
+            clusters: vec![
+                cluster("web", "us-east-1", 1),
+                cluster("api", "us-west-2", 3),
+                cluster("batch", "eu-west-1", 2),
+            ],
+            ..State::default()
+        }
+    }
+
+    fn press(clusters: &mut Clusters, state: &State, c: char) {
+        clusters.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        clusters.set_state(state);
+    }
+
+    fn names(clusters: &Clusters) -> Vec<&str> {
+        clusters
+            .props
+            .clusters
+            .iter()
+            .map(|cluster| cluster.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn sorts_by_name_first() {
+        let mut clusters = Clusters::new();
+        clusters.set_state(&state());
+
+        assert_eq!(names(&clusters), ["api", "batch", "web"]);
+    }
+
+    #[test]
+    fn sorts_by_selected_column() {
+        let state = state();
+        let mut clusters = Clusters::new();
+        clusters.set_state(&state);
+
+        press(&mut clusters, &state, 's');
+        assert_eq!(clusters.sort.header(1, HEADERS[1]), "REGION↑");
+        assert_eq!(names(&clusters), ["batch", "web", "api"]);
+
+        for _ in 0..4 {
+            press(&mut clusters, &state, 's');
+        }
+        assert_eq!(clusters.sort.header(5, HEADERS[5]), "RUNNING↑");
+        assert_eq!(names(&clusters), ["web", "batch", "api"]);
+    }
+
+    #[test]
+    fn reverses_with_shift() {
+        let state = state();
+        let mut clusters = Clusters::new();
+        clusters.set_state(&state);
+
+        press(&mut clusters, &state, 'S');
+        assert_eq!(names(&clusters), ["web", "batch", "api"]);
+
+        press(&mut clusters, &state, 'S');
+        assert_eq!(names(&clusters), ["api", "batch", "web"]);
+    }
+}
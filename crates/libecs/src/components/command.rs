@@ -0,0 +1,330 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    layout::Rect,
+    prelude::Stylize,
+    style::{palette::tailwind, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use crate::{
+    route::Route,
+    state_store::{action::Action, State},
+};
+
+use super::Component;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Resource {
+    Clusters,
+    Services,
+    Tasks,
+    TaskDefinitions,
+    ContainerInstances,
+}
+
+impl Resource {
+    const ALL: [Resource; 5] = [
+        Resource::Clusters,
+        Resource::Services,
+        Resource::Tasks,
+        Resource::TaskDefinitions,
+        Resource::ContainerInstances,
+    ];
+
+    // the first alias is the canonical name used for completion
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            Resource::Clusters => &["clusters", "cluster", "cl"],
+            Resource::Services => &["services", "service", "svc"],
+            Resource::Tasks => &["tasks", "task"],
+            Resource::TaskDefinitions => &["taskdefinitions", "taskdefinition", "td"],
+            Resource::ContainerInstances => &["containerinstances", "containerinstance", "ci"],
+        }
+    }
+
+    fn parse(input: &str) -> Option<Resource> {
+        Resource::ALL
+            .into_iter()
+            .find(|resource| resource.aliases().contains(&input))
+    }
+
+    fn route(&self, cluster_arn: Option<&str>, service_name: Option<&str>) -> Option<Route> {
+        match self {
+            Resource::Clusters => Some(Route::Clusters),
+            Resource::TaskDefinitions => Some(Route::TaskDefinitions {
+                cluster_arn: cluster_arn.map(str::to_string),
+            }),
+            Resource::Services => cluster_arn.map(|cluster_arn| Route::Services {
+                cluster_arn: cluster_arn.to_string(),
+            }),
+            Resource::Tasks => cluster_arn.map(|cluster_arn| Route::Tasks {
+                cluster_arn: cluster_arn.to_string(),
+                service_name: service_name.map(str::to_string),
+            }),
+            Resource::ContainerInstances => {
+                cluster_arn.map(|cluster_arn| Route::ContainerInstances {
+                    cluster_arn: cluster_arn.to_string(),
+                })
+            }
+        }
+    }
+}
+
+// commands that open a popup instead of a resource view
+const CONTEXT_ALIASES: [&str; 2] = ["ctx", "context"];
+
+#[derive(Default)]
+pub struct CommandPrompt {
+    active: bool,
+    input: String,
+    message: Option<String>,
+    // resource context of the screen the prompt was opened from
+    cluster_arn: Option<String>,
+    service_name: Option<String>,
+}
+
+impl CommandPrompt {
+    pub fn open(&mut self, cluster_arn: Option<String>, service_name: Option<String>) {
+        self.active = true;
+        self.input.clear();
+        self.message = None;
+        self.cluster_arn = cluster_arn;
+        self.service_name = service_name;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    fn close(&mut self) {
+        self.active = false;
+        self.input.clear();
+    }
+
+    fn candidates(&self) -> Vec<&'static str> {
+        Resource::ALL
+            .into_iter()
+            .flat_map(|resource| resource.aliases().iter().copied())
+            .chain(CONTEXT_ALIASES)
+            .filter(|alias| alias.starts_with(self.input.as_str()))
+            .collect()
+    }
+
+    fn complete(&mut self) {
+        let candidates = self.candidates();
+        let Some(first) = candidates.first() else {
+            return;
+        };
+
+        // extend the input to the longest prefix shared by every candidate
+        let prefix_len = candidates.iter().fold(first.len(), |len, candidate| {
+            first
+                .chars()
+                .zip(candidate.chars())
+                .take(len)
+                .take_while(|(a, b)| a == b)
+                .count()
+        });
+        self.input = first[..prefix_len].to_string();
+    }
+
+    fn submit(&mut self) -> Action {
+        let input = self.input.trim().to_string();
+
+        if CONTEXT_ALIASES.contains(&input.as_str()) {
+            self.close();
+            return Action::OpenContextSwitcher;
+        }
+
+        let Some(resource) = Resource::parse(&input) else {
+            self.message = Some(format!("unknown resource '{input}'"));
+            return Action::Noop;
+        };
+
+        match resource.route(self.cluster_arn.as_deref(), self.service_name.as_deref()) {
+            Some(route) => {
+                self.close();
+                Action::Switch(route)
+            }
+            None => {
+                self.message = Some(format!("select a cluster before opening '{input}'"));
+                Action::Noop
+            }
+        }
+    }
+}
+
+impl Component for CommandPrompt {
+    fn set_state(&mut self, state: &State) {
+        let _ = state;
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::Noop;
+        }
+
+        match key.code {
+            KeyCode::Esc => {
+                self.close();
+                Action::Noop
+            }
+            KeyCode::Enter => self.submit(),
+            KeyCode::Tab => {
+                self.complete();
+                Action::Noop
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.message = None;
+                Action::Noop
+            }
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                self.message = None;
+                Action::Noop
+            }
+            _ => Action::Noop,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let hint = match &self.message {
+            Some(message) => Span::from(format!("  {message}")).fg(tailwind::RED.c400),
+            None => Span::from(format!("  {}", self.candidates().join(" "))).dark_gray(),
+        };
+        let line = Line::from(vec![
+            Span::from(format!(":{}", self.input)).fg(tailwind::CYAN.c100),
+            hint,
+        ]);
+
+        frame.render_widget(
+            Paragraph::new(line)
+                .block(Block::bordered().style(Style::default().bold().fg(tailwind::CYAN.c200))),
+            rect,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+
+    const CLUSTER_ARN: &str = "arn:aws:ecs:us-east-1:123456789012:cluster/demo";
+
+    fn prompt(cluster_arn: Option<&str>, service_name: Option<&str>) -> CommandPrompt {
+        let mut prompt = CommandPrompt::default();
+        prompt.open(
+            cluster_arn.map(str::to_string),
+            service_name.map(str::to_string),
+        );
+        prompt
+    }
+
+    fn press(prompt: &mut CommandPrompt, code: KeyCode) -> Action {
+        prompt.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn run(prompt: &mut CommandPrompt, input: &str) -> Action {
+        for c in input.chars() {
+            press(prompt, KeyCode::Char(c));
+        }
+        press(prompt, KeyCode::Enter)
+    }
+
+    fn completed(input: &str) -> String {
+        let mut prompt = prompt(None, None);
+        for c in input.chars() {
+            press(&mut prompt, KeyCode::Char(c));
+        }
+        press(&mut prompt, KeyCode::Tab);
+        prompt.input
+    }
+
+    #[test]
+    fn completes_longest_shared_prefix() {
+        assert_eq!(completed("clu"), "cluster");
+        assert_eq!(completed("ta"), "task");
+        assert_eq!(completed("taskd"), "taskdefinition");
+        assert_eq!(completed("co"), "cont");
+        assert_eq!(completed("conta"), "containerinstance");
+        assert_eq!(completed("sv"), "svc");
+        // nothing to complete to
+        assert_eq!(completed("x"), "x");
+    }
+
+    #[test]
+    fn switches_to_route_of_alias() {
+        for alias in ["clusters", "cluster", "cl"] {
+            assert_eq!(
+                run(&mut prompt(None, None), alias),
+                Action::Switch(Route::Clusters)
+            );
+        }
+        for alias in ["taskdefinitions", "td"] {
+            assert_eq!(
+                run(&mut prompt(None, None), alias),
+                Action::Switch(Route::TaskDefinitions { cluster_arn: None })
+            );
+        }
+        // listed in the account and region of the cluster
+        assert_eq!(
+            run(&mut prompt(Some(CLUSTER_ARN), None), "td"),
+            Action::Switch(Route::TaskDefinitions {
+                cluster_arn: Some(CLUSTER_ARN.to_string())
+            })
+        );
+        assert_eq!(
+            run(&mut prompt(Some(CLUSTER_ARN), None), "svc"),
+            Action::Switch(Route::Services {
+                cluster_arn: CLUSTER_ARN.to_string()
+            })
+        );
+        assert_eq!(
+            run(&mut prompt(Some(CLUSTER_ARN), Some("web")), " task "),
+            Action::Switch(Route::Tasks {
+                cluster_arn: CLUSTER_ARN.to_string(),
+                service_name: Some("web".to_string()),
+            })
+        );
+        assert_eq!(
+            run(&mut prompt(Some(CLUSTER_ARN), None), "ci"),
+            Action::Switch(Route::ContainerInstances {
+                cluster_arn: CLUSTER_ARN.to_string()
+            })
+        );
+
+        let mut context = prompt(None, None);
+        assert_eq!(run(&mut context, "ctx"), Action::OpenContextSwitcher);
+        assert!(!context.is_active());
+    }
+
+    #[test]
+    fn keeps_prompt_open_on_unknown_command() {
+        let mut prompt = prompt(None, None);
+
+        assert_eq!(run(&mut prompt, "pods"), Action::Noop);
+        assert!(prompt.is_active());
+        assert_eq!(prompt.message.as_deref(), Some("unknown resource 'pods'"));
+
+        // typing again clears the message
+        press(&mut prompt, KeyCode::Backspace);
+        assert_eq!(prompt.message, None);
+    }
+
+    #[test]
+    fn needs_cluster_for_cluster_resources() {
+        let mut prompt = prompt(None, None);
+
+        assert_eq!(run(&mut prompt, "tasks"), Action::Noop);
+        assert!(prompt.is_active());
+        assert_eq!(
+            prompt.message.as_deref(),
+            Some("select a cluster before opening 'tasks'")
+        );
+    }
+}
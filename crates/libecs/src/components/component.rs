@@ -7,6 +7,7 @@ use tokio::sync::mpsc::UnboundedSender;
 use crate::state_store::action::Action;
 use crate::state_store::State;
 
+#[allow(dead_code)]
 #[derive(Clone, Debug, PartialEq)]
 pub enum Event {
     Init,
@@ -29,6 +30,12 @@ pub trait Component {
     fn init(&mut self) -> Result<()> {
         Ok(())
     }
+    // whether the component needs the key before the app's global bindings,
+    // e.g. while text is being typed into it
+    fn captures_key(&self, key: &KeyEvent) -> bool {
+        let _ = key;
+        false
+    }
     fn set_state(&mut self, state: &State);
     fn handle_events(&mut self, event: Option<Event>) -> Action {
         match event {
@@ -45,6 +52,7 @@ pub trait Component {
         let _ = mouse;
         Action::Noop
     }
+    #[allow(dead_code)]
     fn update(&mut self, action: Action) -> Action {
         let _ = action;
         Action::Noop
@@ -0,0 +1,153 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    layout::{Alignment, Rect},
+    prelude::Stylize,
+    style::{palette::tailwind, Style},
+    text::{Line, Span},
+    widgets::{
+        block::{Position, Title},
+        Block, Clear, Paragraph, Wrap,
+    },
+    Frame,
+};
+
+use crate::state_store::{action::Action, State};
+
+use super::Component;
+
+// turns the text typed into the dialog into the action to run, or into the
+// message shown when the text is not valid
+type Submit = Box<dyn Fn(&str) -> Result<Action, String> + Send>;
+
+// dialog a screen opens over itself before a call that changes resources,
+// `details` are shown as `label: value` lines above the optional input
+pub struct Confirm {
+    title: String,
+    details: Vec<(String, String)>,
+    // label of the text input, none for a plain yes/no dialog
+    input_label: Option<String>,
+    input: String,
+    submit: Submit,
+    message: Option<String>,
+    closed: bool,
+}
+
+impl Confirm {
+    pub fn new(
+        title: impl Into<String>,
+        details: Vec<(String, String)>,
+        submit: impl Fn(&str) -> Result<Action, String> + Send + 'static,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            details,
+            input_label: None,
+            input: String::new(),
+            submit: Box::new(submit),
+            message: None,
+            closed: false,
+        }
+    }
+
+    pub fn with_input(mut self, label: impl Into<String>, value: impl Into<String>) -> Self {
+        self.input_label = Some(label.into());
+        self.input = value.into();
+        self
+    }
+
+    // set once the dialog was submitted or cancelled, the screen drops it
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    // centered over the screen
+    pub fn area(&self, rect: Rect) -> Rect {
+        let width = 70.min(rect.width);
+        let height = (self.details.len() as u16 + 6).min(rect.height);
+        Rect::new(
+            rect.x + (rect.width - width) / 2,
+            rect.y + (rect.height - height) / 2,
+            width,
+            height,
+        )
+    }
+}
+
+impl Component for Confirm {
+    fn set_state(&mut self, state: &State) {
+        let _ = state;
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::Noop;
+        }
+
+        match key.code {
+            KeyCode::Esc => self.closed = true,
+            KeyCode::Enter => match (self.submit)(self.input.trim()) {
+                Ok(action) => {
+                    self.closed = true;
+                    return action;
+                }
+                Err(message) => self.message = Some(message),
+            },
+            KeyCode::Backspace if self.input_label.is_some() => {
+                self.input.pop();
+                self.message = None;
+            }
+            KeyCode::Char(c) if self.input_label.is_some() => {
+                self.input.push(c);
+                self.message = None;
+            }
+            _ => {}
+        }
+
+        Action::Noop
+    }
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let label_width = self
+            .details
+            .iter()
+            .map(|(label, _)| label.len())
+            .max()
+            .unwrap_or_default();
+        let mut lines = self
+            .details
+            .iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::from(format!("{label:<label_width$}  ")).dark_gray(),
+                    Span::from(value.clone()).fg(tailwind::CYAN.c100),
+                ])
+            })
+            .collect::<Vec<_>>();
+        lines.push(Line::default());
+        if let Some(label) = &self.input_label {
+            lines.push(Line::from(vec![
+                Span::from(format!("{label}: ")),
+                Span::from(format!("{}_", self.input)).fg(tailwind::CYAN.c100),
+            ]));
+        }
+        if let Some(message) = &self.message {
+            lines.push(Line::from(message.clone()).fg(tailwind::RED.c400));
+        }
+
+        frame.render_widget(Clear, rect);
+        frame.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                Block::bordered()
+                    .title(format!(" {} ", self.title))
+                    .title(
+                        Title::from(" <enter> Confirm <esc> Cancel ")
+                            .position(Position::Bottom)
+                            .alignment(Alignment::Right),
+                    )
+                    .title_alignment(Alignment::Center)
+                    .style(Style::default().bold().fg(tailwind::YELLOW.c300)),
+            ),
+            rect,
+        );
+    }
+}
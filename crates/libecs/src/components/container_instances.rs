@@ -0,0 +1,208 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    layout::{Alignment, Constraint, Rect},
+    prelude::Stylize,
+    style::{palette::tailwind, Modifier, Style},
+    widgets::{Block, Cell, Row, Table, TableState},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    route::{resource_name, DescribeTarget, Route},
+    state_store::{action::Action, ContainerInstanceItem, Listing, State},
+};
+
+use super::{
+    filter::Filter,
+    table::{clamp_selection, loading_footer, select_next, select_previous},
+    Component,
+};
+
+#[derive(Default)]
+struct Props {
+    container_instances: Vec<ContainerInstanceItem>,
+    loading: bool,
+}
+
+impl Props {
+    fn from_state(state: &State, cluster_arn: &str, filter: &Filter) -> Self {
+        let container_instances = state
+            .container_instances
+            .get(cluster_arn)
+            .into_iter()
+            .flatten()
+            .filter(|container_instance| {
+                filter.matches(&[
+                    &container_instance.id,
+                    &container_instance.ec2_instance_id,
+                    &container_instance.status,
+                ])
+            })
+            .cloned()
+            .collect();
+
+        Self {
+            container_instances,
+            loading: state.loading.contains(&Listing::ContainerInstances {
+                cluster_arn: cluster_arn.to_string(),
+            }),
+        }
+    }
+}
+
+pub struct ContainerInstances {
+    action_tx: Option<UnboundedSender<Action>>,
+    cluster_arn: String,
+    props: Props,
+    filter: Filter,
+    table_state: TableState,
+}
+
+impl ContainerInstances {
+    pub fn new(cluster_arn: String) -> Self {
+        Self {
+            action_tx: None,
+            cluster_arn,
+            props: Props::default(),
+            filter: Filter::default(),
+            table_state: TableState::default().with_selected(None),
+        }
+    }
+}
+
+impl Component for ContainerInstances {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn init(&mut self) -> Result<()> {
+        if let Some(tx) = self.action_tx.clone() {
+            tx.send(Action::GetContainerInstances {
+                cluster_arn: self.cluster_arn.clone(),
+            })?;
+        }
+        Ok(())
+    }
+
+    fn captures_key(&self, key: &KeyEvent) -> bool {
+        self.filter.captures_key(key)
+    }
+
+    fn set_state(&mut self, state: &State) {
+        self.props = Props::from_state(state, &self.cluster_arn, &self.filter);
+        clamp_selection(&mut self.table_state, self.props.container_instances.len());
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::Noop;
+        }
+
+        if self.filter.handle_key_event(key) {
+            return Action::Noop;
+        }
+
+        match key.code {
+            KeyCode::Char('k') => {
+                select_previous(&mut self.table_state, self.props.container_instances.len());
+                Action::Noop
+            }
+            KeyCode::Char('j') => {
+                select_next(&mut self.table_state, self.props.container_instances.len());
+                Action::Noop
+            }
+            KeyCode::Char('d') => match self.table_state.selected() {
+                Some(i) => Action::Navigate(Route::Describe {
+                    target: DescribeTarget::ContainerInstance {
+                        cluster_arn: self.cluster_arn.clone(),
+                        container_instance_arn: self.props.container_instances[i].arn.clone(),
+                    },
+                }),
+                None => Action::Noop,
+            },
+            _ => Action::Noop,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let selected_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(tailwind::CYAN.c100);
+
+        let header = [
+            "ID",
+            "EC2 INSTANCE",
+            "STATUS",
+            "AGENT CONNECTED",
+            "RUNNING",
+            "PENDING",
+            "AGENT VERSION",
+            "CPU",
+            "MEMORY",
+        ]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(
+            Style::default()
+                .bold()
+                .bg(tailwind::CYAN.c200)
+                .fg(tailwind::BLACK),
+        );
+        let rows = self.props.container_instances.iter().map(|data| {
+            let item = [
+                data.id.clone(),
+                data.ec2_instance_id.clone(),
+                data.status.clone(),
+                data.agent_connected.to_string(),
+                data.running_tasks_count.to_string(),
+                data.pending_tasks_count.to_string(),
+                data.agent_version.clone(),
+                data.cpu.clone(),
+                data.memory.clone(),
+            ];
+            item.into_iter()
+                .map(|content| Cell::from(self.filter.highlight(content)))
+                .collect::<Row>()
+        });
+
+        let mut t = Table::new(
+            rows,
+            [
+                Constraint::Length(32),
+                Constraint::Length(19),
+                Constraint::Length(10),
+                Constraint::Length(15),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(13),
+                Constraint::Length(11),
+                Constraint::Min(11),
+            ],
+        )
+        .header(header)
+        .highlight_style(selected_style);
+        if self.props.loading {
+            t = t.footer(loading_footer(self.props.container_instances.len()));
+        }
+
+        frame.render_stateful_widget(
+            t.block(
+                Block::bordered()
+                    .title(format!(
+                        " ContainerInstances({})[{}] {}",
+                        resource_name(&self.cluster_arn),
+                        self.props.container_instances.len(),
+                        self.filter.title()
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .style(Style::default().bold().fg(tailwind::CYAN.c200)),
+            ),
+            rect,
+            &mut self.table_state,
+        )
+    }
+}
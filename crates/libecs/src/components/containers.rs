@@ -0,0 +1,219 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    layout::{Alignment, Constraint, Rect},
+    prelude::Stylize,
+    style::{palette::tailwind, Modifier, Style},
+    widgets::{Block, Cell, Row, Table, TableState},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    route::{resource_id, DescribeTarget, Route},
+    state_store::{action::Action, ContainerItem, State},
+};
+
+use super::{
+    filter::Filter,
+    table::{clamp_selection, select_next, select_previous},
+    Component,
+};
+
+#[derive(Default)]
+struct Props {
+    containers: Vec<ContainerItem>,
+}
+
+impl Props {
+    fn from_state(state: &State, task_arn: &str, filter: &Filter) -> Self {
+        let containers = state
+            .containers
+            .get(task_arn)
+            .into_iter()
+            .flatten()
+            .filter(|container| {
+                filter.matches(&[
+                    &container.name,
+                    &container.image,
+                    &container.last_status,
+                    &container.reason,
+                ])
+            })
+            .cloned()
+            .collect();
+
+        Self { containers }
+    }
+}
+
+pub struct Containers {
+    action_tx: Option<UnboundedSender<Action>>,
+    cluster_arn: String,
+    task_arn: String,
+    props: Props,
+    filter: Filter,
+    table_state: TableState,
+}
+
+impl Containers {
+    pub fn new(cluster_arn: String, task_arn: String) -> Self {
+        Self {
+            action_tx: None,
+            cluster_arn,
+            task_arn,
+            props: Props::default(),
+            filter: Filter::default(),
+            table_state: TableState::default().with_selected(None),
+        }
+    }
+}
+
+impl Component for Containers {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn init(&mut self) -> Result<()> {
+        if let Some(tx) = self.action_tx.clone() {
+            tx.send(Action::DescribeTask {
+                cluster_arn: self.cluster_arn.clone(),
+                task_arn: self.task_arn.clone(),
+            })?;
+        }
+        Ok(())
+    }
+
+    fn captures_key(&self, key: &KeyEvent) -> bool {
+        self.filter.captures_key(key)
+    }
+
+    fn set_state(&mut self, state: &State) {
+        self.props = Props::from_state(state, &self.task_arn, &self.filter);
+        clamp_selection(&mut self.table_state, self.props.containers.len());
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::Noop;
+        }
+
+        if self.filter.handle_key_event(key) {
+            return Action::Noop;
+        }
+
+        match key.code {
+            KeyCode::Char('k') => {
+                select_previous(&mut self.table_state, self.props.containers.len());
+                Action::Noop
+            }
+            KeyCode::Char('j') => {
+                select_next(&mut self.table_state, self.props.containers.len());
+                Action::Noop
+            }
+            KeyCode::Char('l') => match self.table_state.selected() {
+                Some(i) => Action::Navigate(Route::Logs {
+                    cluster_arn: self.cluster_arn.clone(),
+                    task_arn: self.task_arn.clone(),
+                    container_name: self.props.containers[i].name.clone(),
+                }),
+                None => Action::Noop,
+            },
+            KeyCode::Char('s') => match self.table_state.selected() {
+                Some(i) => Action::ExecuteCommand {
+                    cluster_arn: self.cluster_arn.clone(),
+                    task_arn: self.task_arn.clone(),
+                    container_name: self.props.containers[i].name.clone(),
+                },
+                None => Action::Noop,
+            },
+            // containers are only described as part of their task
+            KeyCode::Char('d') => Action::Navigate(Route::Describe {
+                target: DescribeTarget::Task {
+                    cluster_arn: self.cluster_arn.clone(),
+                    task_arn: self.task_arn.clone(),
+                },
+            }),
+            _ => Action::Noop,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let selected_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(tailwind::CYAN.c100);
+
+        let header = [
+            "NAME",
+            "IMAGE",
+            "IMAGE DIGEST",
+            "LAST STATUS",
+            "EXIT CODE",
+            "REASON",
+            "HEALTH",
+            "RUNTIME ID",
+            "EXEC AGENT",
+        ]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(
+            Style::default()
+                .bold()
+                .bg(tailwind::CYAN.c200)
+                .fg(tailwind::BLACK),
+        );
+        let rows = self.props.containers.iter().map(|data| {
+            let item = [
+                data.name.clone(),
+                data.image.clone(),
+                data.image_digest.clone(),
+                data.last_status.clone(),
+                data.exit_code
+                    .map(|exit_code| exit_code.to_string())
+                    .unwrap_or_default(),
+                data.reason.clone(),
+                data.health_status.clone(),
+                data.runtime_id.clone(),
+                data.exec_agent_status.clone(),
+            ];
+            item.into_iter()
+                .map(|content| Cell::from(self.filter.highlight(content)))
+                .collect::<Row>()
+        });
+
+        let t = Table::new(
+            rows,
+            [
+                Constraint::Min(16),
+                Constraint::Min(30),
+                Constraint::Min(20),
+                Constraint::Length(12),
+                Constraint::Length(10),
+                Constraint::Min(16),
+                Constraint::Length(10),
+                Constraint::Min(16),
+                Constraint::Length(11),
+            ],
+        )
+        .header(header)
+        .highlight_style(selected_style);
+
+        frame.render_stateful_widget(
+            t.block(
+                Block::bordered()
+                    .title(format!(
+                        " Containers({})[{}] {}",
+                        resource_id(&self.task_arn),
+                        self.props.containers.len(),
+                        self.filter.title()
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .style(Style::default().bold().fg(tailwind::CYAN.c200)),
+            ),
+            rect,
+            &mut self.table_state,
+        )
+    }
+}
@@ -14,12 +14,18 @@ use super::Component;
 
 #[derive(Default)]
 struct Props {
+    profile: Option<String>,
+    region: Option<String>,
+    account: Option<String>,
     arn: Option<String>,
 }
 
 impl From<&State> for Props {
     fn from(state: &State) -> Self {
         Props {
+            profile: state.profile.clone(),
+            region: state.region.clone(),
+            account: state.account.clone(),
             arn: state.caller_arn.clone(),
         }
     }
@@ -29,6 +35,19 @@ impl From<&State> for Props {
 pub struct Context {
     command_tx: Option<UnboundedSender<Action>>,
     props: Props,
+    breadcrumb: Vec<String>,
+    refresh: String,
+}
+
+impl Context {
+    pub fn set_breadcrumb(&mut self, breadcrumb: Vec<String>) {
+        self.breadcrumb = breadcrumb;
+    }
+
+    // auto-refresh interval or why it is not running, kept by the app
+    pub fn set_refresh(&mut self, refresh: String) {
+        self.refresh = refresh;
+    }
 }
 
 impl Component for Context {
@@ -56,12 +75,29 @@ impl Component for Context {
                 .areas(outer_area);
 
         let title_items: Vec<ListItem> = vec![
+            ListItem::new(Line::from("Profile: ")),
+            ListItem::new(Line::from("Region:  ")),
+            ListItem::new(Line::from("Account: ")),
             ListItem::new(Line::from("IAM ARN: ")),
             ListItem::new(Line::from("Cluster: ")),
+            ListItem::new(Line::from("Refresh: ")),
+        ];
+        let value_items: Vec<ListItem> = vec![
+            ListItem::new(Line::yellow(
+                self.props.profile.clone().unwrap_or_default().into(),
+            )),
+            ListItem::new(Line::yellow(
+                self.props.region.clone().unwrap_or_default().into(),
+            )),
+            ListItem::new(Line::yellow(
+                self.props.account.clone().unwrap_or_default().into(),
+            )),
+            ListItem::new(Line::yellow(
+                self.props.arn.clone().unwrap_or("".to_string()).into(),
+            )),
+            ListItem::new(Line::yellow(self.breadcrumb.join(" > ").into())),
+            ListItem::new(Line::yellow(self.refresh.clone().into())),
         ];
-        let value_items: Vec<ListItem> = vec![ListItem::new(Line::yellow(
-            self.props.arn.clone().unwrap_or("".to_string()).into(),
-        ))];
 
         frame.render_widget(List::new(title_items), title_area);
         frame.render_widget(List::new(value_items), value_area);
@@ -0,0 +1,226 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    prelude::Stylize,
+    style::{palette::tailwind, Modifier, Style},
+    widgets::{
+        block::{Position, Title},
+        Block, Clear, List, ListState,
+    },
+    Frame,
+};
+
+use crate::state_store::{action::Action, State, REGIONS};
+
+use super::{
+    table::{next_index, previous_index},
+    Component,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Focus {
+    Profiles,
+    Regions,
+}
+
+#[derive(Default)]
+struct Props {
+    profiles: Vec<String>,
+    profile: Option<String>,
+    region: Option<String>,
+}
+
+impl From<&State> for Props {
+    fn from(state: &State) -> Self {
+        Self {
+            profiles: state.profiles.clone(),
+            profile: state.profile.clone(),
+            region: state.region.clone(),
+        }
+    }
+}
+
+// popup opened with `p` or `:ctx` to pick the profile and region the app
+// talks to
+pub struct ContextSwitcher {
+    active: bool,
+    focus: Focus,
+    props: Props,
+    profile_state: ListState,
+    region_state: ListState,
+}
+
+impl Default for ContextSwitcher {
+    fn default() -> Self {
+        Self {
+            active: false,
+            focus: Focus::Profiles,
+            props: Props::default(),
+            profile_state: ListState::default(),
+            region_state: ListState::default(),
+        }
+    }
+}
+
+impl ContextSwitcher {
+    pub fn open(&mut self) {
+        self.active = true;
+        self.focus = Focus::Profiles;
+        self.profile_state.select(None);
+        self.region_state.select(
+            self.props
+                .region
+                .as_deref()
+                .and_then(|region| REGIONS.iter().position(|r| *r == region))
+                .or(Some(0)),
+        );
+        self.select_current_profile();
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    fn close(&mut self) {
+        self.active = false;
+    }
+
+    // the profiles are read when the popup opens, select the active one
+    // once they arrive
+    fn select_current_profile(&mut self) {
+        if self.profile_state.selected().is_some() || self.props.profiles.is_empty() {
+            return;
+        }
+        let i = self
+            .props
+            .profile
+            .as_ref()
+            .and_then(|profile| self.props.profiles.iter().position(|p| p == profile))
+            .unwrap_or(0);
+        self.profile_state.select(Some(i));
+    }
+
+    fn focused(&mut self) -> (&mut ListState, usize) {
+        match self.focus {
+            Focus::Profiles => (&mut self.profile_state, self.props.profiles.len()),
+            Focus::Regions => (&mut self.region_state, REGIONS.len()),
+        }
+    }
+
+    fn next(&mut self) {
+        let (list_state, len) = self.focused();
+        if let Some(i) = next_index(list_state.selected(), len) {
+            list_state.select(Some(i));
+        }
+    }
+
+    fn previous(&mut self) {
+        let (list_state, len) = self.focused();
+        if let Some(i) = previous_index(list_state.selected(), len) {
+            list_state.select(Some(i));
+        }
+    }
+
+    fn submit(&mut self) -> Action {
+        let profile = self
+            .profile_state
+            .selected()
+            .and_then(|i| self.props.profiles.get(i))
+            .cloned()
+            .or_else(|| self.props.profile.clone());
+        let region = self.region_state.selected().map(|i| REGIONS[i].to_string());
+
+        match (profile, region) {
+            (Some(profile), Some(region)) => {
+                self.close();
+                Action::SwitchContext { profile, region }
+            }
+            _ => Action::Noop,
+        }
+    }
+
+    // centered over the content area
+    pub fn area(&self, rect: Rect) -> Rect {
+        let width = 60.min(rect.width);
+        let height = 20.min(rect.height);
+        Rect::new(
+            rect.x + (rect.width - width) / 2,
+            rect.y + (rect.height - height) / 2,
+            width,
+            height,
+        )
+    }
+}
+
+impl Component for ContextSwitcher {
+    fn set_state(&mut self, state: &State) {
+        self.props = Props::from(state);
+        if self.active {
+            self.select_current_profile();
+        }
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::Noop;
+        }
+
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Enter => return self.submit(),
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Char('h') | KeyCode::Char('l') => {
+                self.focus = match self.focus {
+                    Focus::Profiles => Focus::Regions,
+                    Focus::Regions => Focus::Profiles,
+                }
+            }
+            KeyCode::Char('j') | KeyCode::Down => self.next(),
+            KeyCode::Char('k') | KeyCode::Up => self.previous(),
+            _ => {}
+        }
+
+        Action::Noop
+    }
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let selected_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(tailwind::CYAN.c100);
+        let list_block = |title: &'static str, focus: Focus| {
+            let style = match self.focus == focus {
+                true => Style::default().fg(tailwind::CYAN.c200),
+                false => Style::default().dark_gray(),
+            };
+            Block::bordered().title(title).style(style)
+        };
+
+        let block = Block::bordered()
+            .title(" Context ")
+            .title(
+                Title::from(" <tab> Switch list <enter> Apply <esc> Cancel ")
+                    .position(Position::Bottom)
+                    .alignment(Alignment::Right),
+            )
+            .title_alignment(Alignment::Center)
+            .style(Style::default().bold().fg(tailwind::CYAN.c200));
+        let inner = block.inner(rect);
+        let [profiles_area, regions_area] =
+            Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .areas(inner);
+
+        let profiles = match self.props.profiles.is_empty() {
+            true => List::new(["no profiles found"]),
+            false => List::new(self.props.profiles.clone()),
+        }
+        .block(list_block(" Profiles ", Focus::Profiles))
+        .highlight_style(selected_style);
+        let regions = List::new(REGIONS)
+            .block(list_block(" Regions ", Focus::Regions))
+            .highlight_style(selected_style);
+
+        frame.render_widget(Clear, rect);
+        frame.render_widget(block, rect);
+        frame.render_stateful_widget(profiles, profiles_area, &mut self.profile_state);
+        frame.render_stateful_widget(regions, regions_area, &mut self.region_state);
+    }
+}
@@ -0,0 +1,234 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    layout::{Alignment, Constraint, Rect},
+    prelude::Stylize,
+    style::{palette::tailwind, Modifier, Style},
+    widgets::{Block, Cell, Row, Table, TableState},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    route::{resource_id, DescribeTarget, Route},
+    state_store::{action::Action, DeploymentItem, State},
+};
+
+use super::{
+    filter::Filter,
+    table::{age, clamp_selection, select_next, select_previous},
+    Component,
+};
+
+#[derive(Default)]
+struct Props {
+    deployments: Vec<DeploymentItem>,
+    // the rollout is still polled by the store
+    deploying: bool,
+}
+
+impl Props {
+    fn from_state(state: &State, service_arn: &str, filter: &Filter) -> Self {
+        let deployments = state
+            .deployments
+            .get(service_arn)
+            .into_iter()
+            .flatten()
+            .filter(|deployment| {
+                filter.matches(&[
+                    &deployment.id,
+                    &deployment.status,
+                    &deployment.task_definition,
+                    deployment.rollout_state.as_deref().unwrap_or_default(),
+                ])
+            })
+            .cloned()
+            .collect();
+
+        Self {
+            deployments,
+            deploying: state.deploying.contains(service_arn),
+        }
+    }
+}
+
+// rollout of a service, opened after a forced deployment and polled by the
+// store until the primary deployment completed or failed
+pub struct Deployments {
+    action_tx: Option<UnboundedSender<Action>>,
+    cluster_arn: String,
+    service_arn: String,
+    props: Props,
+    filter: Filter,
+    table_state: TableState,
+}
+
+impl Deployments {
+    pub fn new(cluster_arn: String, service_arn: String) -> Self {
+        Self {
+            action_tx: None,
+            cluster_arn,
+            service_arn,
+            props: Props::default(),
+            filter: Filter::default(),
+            table_state: TableState::default().with_selected(None),
+        }
+    }
+}
+
+impl Component for Deployments {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn init(&mut self) -> Result<()> {
+        if let Some(tx) = self.action_tx.clone() {
+            tx.send(Action::GetDeployments {
+                cluster_arn: self.cluster_arn.clone(),
+                service_arn: self.service_arn.clone(),
+            })?;
+        }
+        Ok(())
+    }
+
+    fn captures_key(&self, key: &KeyEvent) -> bool {
+        self.filter.captures_key(key)
+    }
+
+    fn set_state(&mut self, state: &State) {
+        self.props = Props::from_state(state, &self.service_arn, &self.filter);
+        clamp_selection(&mut self.table_state, self.props.deployments.len());
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::Noop;
+        }
+
+        if self.filter.handle_key_event(key) {
+            return Action::Noop;
+        }
+
+        match key.code {
+            KeyCode::Char('k') => {
+                select_previous(&mut self.table_state, self.props.deployments.len());
+                Action::Noop
+            }
+            KeyCode::Char('j') => {
+                select_next(&mut self.table_state, self.props.deployments.len());
+                Action::Noop
+            }
+            KeyCode::Char('e') => Action::Navigate(Route::Events {
+                cluster_arn: self.cluster_arn.clone(),
+                service_arn: self.service_arn.clone(),
+            }),
+            KeyCode::Char('h') => Action::Navigate(Route::History {
+                cluster_arn: self.cluster_arn.clone(),
+                service_arn: self.service_arn.clone(),
+            }),
+            // deployments are only described as part of their service
+            KeyCode::Char('d') => Action::Navigate(Route::Describe {
+                target: DescribeTarget::Service {
+                    cluster_arn: self.cluster_arn.clone(),
+                    service_arn: self.service_arn.clone(),
+                },
+            }),
+            _ => Action::Noop,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let selected_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(tailwind::CYAN.c100);
+
+        let header = [
+            "ID",
+            "STATUS",
+            "TASK DEFINITION",
+            "DESIRED",
+            "RUNNING",
+            "PENDING",
+            "FAILED",
+            "ROLLOUT",
+            "UPDATED",
+            "REASON",
+        ]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(
+            Style::default()
+                .bold()
+                .bg(tailwind::CYAN.c200)
+                .fg(tailwind::BLACK),
+        );
+        let rows = self.props.deployments.iter().map(|data| {
+            let rollout_state = data.rollout_state.clone().unwrap_or_default();
+            let color = match rollout_state.as_str() {
+                "FAILED" => Some(tailwind::RED.c400),
+                "IN_PROGRESS" if data.is_primary() => Some(tailwind::YELLOW.c300),
+                _ => None,
+            };
+            let item = [
+                data.id.clone(),
+                data.status.clone(),
+                data.task_definition.clone(),
+                data.desired_count.to_string(),
+                data.running_count.to_string(),
+                data.pending_count.to_string(),
+                data.failed_tasks.to_string(),
+                rollout_state,
+                age(data.updated_at.as_ref()),
+                data.rollout_state_reason.clone(),
+            ];
+            let row = item
+                .into_iter()
+                .map(|content| Cell::from(self.filter.highlight(content)))
+                .collect::<Row>();
+            match color {
+                Some(color) => row.fg(color),
+                None => row,
+            }
+        });
+
+        let t = Table::new(
+            rows,
+            [
+                Constraint::Length(24),
+                Constraint::Length(9),
+                Constraint::Min(20),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(7),
+                Constraint::Length(12),
+                Constraint::Length(8),
+                Constraint::Min(20),
+            ],
+        )
+        .header(header)
+        .highlight_style(selected_style);
+
+        let tracking = match self.props.deploying {
+            true => "(rolling out) ",
+            false => "",
+        };
+        frame.render_stateful_widget(
+            t.block(
+                Block::bordered()
+                    .title(format!(
+                        " Deployments({})[{}] {tracking}{}",
+                        resource_id(&self.service_arn),
+                        self.props.deployments.len(),
+                        self.filter.title()
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .style(Style::default().bold().fg(tailwind::CYAN.c200)),
+            ),
+            rect,
+            &mut self.table_state,
+        )
+    }
+}
@@ -0,0 +1,453 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    layout::{Alignment, Rect},
+    prelude::Stylize,
+    style::{palette::tailwind, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    document::Value,
+    route::DescribeTarget,
+    state_store::{action::Action, State},
+};
+
+use super::{filter::Filter, Component};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Yaml,
+    Json,
+}
+
+pub struct Describe {
+    action_tx: Option<UnboundedSender<Action>>,
+    target: DescribeTarget,
+    value: Option<Value>,
+    format: Format,
+    lines: Vec<Line<'static>>,
+    scroll: usize,
+    search: Filter,
+}
+
+impl Describe {
+    pub fn new(target: DescribeTarget) -> Self {
+        Self {
+            action_tx: None,
+            target,
+            value: None,
+            format: Format::Yaml,
+            lines: vec![],
+            scroll: 0,
+            search: Filter::default(),
+        }
+    }
+
+    fn render_lines(&mut self) {
+        let mut lines = vec![];
+        if let Some(value) = &self.value {
+            match self.format {
+                Format::Yaml => yaml_root(value, &mut lines),
+                Format::Json => json_value(value, 0, indentation(0), "", &mut lines),
+            }
+        }
+        self.lines = lines;
+        self.scroll = self.scroll.min(self.lines.len().saturating_sub(1));
+    }
+
+    fn is_match(&self, line: &Line) -> bool {
+        self.search.is_active() && self.search.matches(&[&plain(line)])
+    }
+
+    fn next_match(&mut self) {
+        if let Some(i) =
+            (self.scroll + 1..self.lines.len()).find(|&i| self.is_match(&self.lines[i]))
+        {
+            self.scroll = i;
+        }
+    }
+
+    fn previous_match(&mut self) {
+        if let Some(i) = (0..self.scroll)
+            .rev()
+            .find(|&i| self.is_match(&self.lines[i]))
+        {
+            self.scroll = i;
+        }
+    }
+}
+
+impl Component for Describe {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn init(&mut self) -> Result<()> {
+        if let Some(tx) = self.action_tx.clone() {
+            tx.send(Action::Describe(self.target.clone()))?;
+        }
+        Ok(())
+    }
+
+    fn captures_key(&self, key: &KeyEvent) -> bool {
+        self.search.captures_key(key)
+    }
+
+    fn set_state(&mut self, state: &State) {
+        let value = state.descriptions.get(&self.target).cloned();
+        if value != self.value {
+            self.value = value;
+            self.render_lines();
+        }
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::Noop;
+        }
+
+        if self.search.handle_key_event(key) {
+            // jump to the first match while typing
+            if let Some(i) = self.lines.iter().position(|line| self.is_match(line)) {
+                self.scroll = i;
+            }
+            return Action::Noop;
+        }
+
+        let last = self.lines.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.scroll = (self.scroll + 1).min(last),
+            KeyCode::Char('k') | KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::PageDown => self.scroll = (self.scroll + 20).min(last),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(20),
+            KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::Char('G') => self.scroll = last,
+            KeyCode::Char('n') => self.next_match(),
+            KeyCode::Char('N') => self.previous_match(),
+            KeyCode::Char('y') => {
+                self.format = match self.format {
+                    Format::Yaml => Format::Json,
+                    Format::Json => Format::Yaml,
+                };
+                self.scroll = 0;
+                self.render_lines();
+            }
+            _ => {}
+        }
+
+        Action::Noop
+    }
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let lines = self
+            .lines
+            .iter()
+            .skip(self.scroll)
+            .take(rect.height as usize)
+            .map(|line| match self.is_match(line) {
+                true => self.search.highlight(plain(line)),
+                false => line.clone(),
+            })
+            .collect::<Vec<Line>>();
+
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::bordered()
+                    .title(format!(
+                        " Describe({})[{}] {}",
+                        self.target.name(),
+                        match self.format {
+                            Format::Yaml => "YAML",
+                            Format::Json => "JSON",
+                        },
+                        self.search.title()
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .style(Style::default().bold().fg(tailwind::CYAN.c200)),
+            ),
+            rect,
+        )
+    }
+}
+
+pub(super) fn plain(line: &Line) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+fn indentation(indent: usize) -> Vec<Span<'static>> {
+    vec![Span::from(" ".repeat(indent))]
+}
+
+fn key_span(key: &str) -> Span<'static> {
+    Span::from(key.to_string()).fg(tailwind::CYAN.c300)
+}
+
+fn scalar_span(value: &Value, quote: bool) -> Span<'static> {
+    match value {
+        Value::Null => Span::from("null").fg(tailwind::YELLOW.c300),
+        Value::Bool(b) => Span::from(b.to_string()).fg(tailwind::YELLOW.c300),
+        Value::Number(n) => Span::from(n.clone()).fg(tailwind::PURPLE.c300),
+        Value::String(s) if quote => Span::from(quoted(s)).fg(tailwind::GREEN.c300),
+        Value::String(s) => Span::from(s.clone()).fg(tailwind::GREEN.c300),
+        Value::Array(_) | Value::Object(_) => Span::from(""),
+    }
+}
+
+fn quoted(s: &str) -> String {
+    format!("{s:?}")
+}
+
+fn line(mut spans: Vec<Span<'static>>, tail: Vec<Span<'static>>) -> Line<'static> {
+    spans.extend(tail);
+    Line::from(spans)
+}
+
+fn json_value(
+    value: &Value,
+    indent: usize,
+    head: Vec<Span<'static>>,
+    suffix: &'static str,
+    lines: &mut Vec<Line<'static>>,
+) {
+    match value {
+        Value::Array(items) if items.is_empty() => {
+            lines.push(line(head, vec![Span::from("[]"), Span::from(suffix)]))
+        }
+        Value::Object(fields) if is_empty_object(fields) => {
+            lines.push(line(head, vec![Span::from("{}"), Span::from(suffix)]))
+        }
+        Value::Array(items) => {
+            lines.push(line(head, vec![Span::from("[")]));
+            for (i, item) in items.iter().enumerate() {
+                let suffix = if i + 1 < items.len() { "," } else { "" };
+                json_value(item, indent + 2, indentation(indent + 2), suffix, lines);
+            }
+            lines.push(line(
+                indentation(indent),
+                vec![Span::from("]"), Span::from(suffix)],
+            ));
+        }
+        Value::Object(fields) => {
+            let fields = fields
+                .iter()
+                .filter(|(_, v)| *v != Value::Null)
+                .collect::<Vec<_>>();
+            lines.push(line(head, vec![Span::from("{")]));
+            for (i, (key, field)) in fields.iter().enumerate() {
+                let suffix = if i + 1 < fields.len() { "," } else { "" };
+                let mut head = indentation(indent + 2);
+                head.extend([key_span(&quoted(key)), Span::from(": ")]);
+                json_value(field, indent + 2, head, suffix, lines);
+            }
+            lines.push(line(
+                indentation(indent),
+                vec![Span::from("}"), Span::from(suffix)],
+            ));
+        }
+        scalar => lines.push(line(
+            head,
+            vec![scalar_span(scalar, true), Span::from(suffix)],
+        )),
+    }
+}
+
+// the YAML lines of a document, as shown by the describe view
+pub(super) fn yaml_lines(value: &Value) -> Vec<Line<'static>> {
+    let mut lines = vec![];
+    yaml_root(value, &mut lines);
+    lines
+}
+
+fn yaml_root(value: &Value, lines: &mut Vec<Line<'static>>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                yaml_field(key, field, 0, lines);
+            }
+        }
+        value => yaml_item(value, 0, lines),
+    }
+}
+
+fn yaml_field(key: &str, value: &Value, indent: usize, lines: &mut Vec<Line<'static>>) {
+    let mut head = indentation(indent);
+    head.extend([key_span(key), Span::from(":")]);
+
+    match value {
+        Value::Null => {}
+        Value::Array(items) if items.is_empty() => lines.push(line(head, vec![Span::from(" []")])),
+        Value::Object(fields) if is_empty_object(fields) => {
+            lines.push(line(head, vec![Span::from(" {}")]))
+        }
+        Value::Array(items) => {
+            lines.push(Line::from(head));
+            for item in items {
+                yaml_item(item, indent, lines);
+            }
+        }
+        Value::Object(fields) => {
+            lines.push(Line::from(head));
+            for (key, field) in fields {
+                yaml_field(key, field, indent + 2, lines);
+            }
+        }
+        scalar => lines.push(line(head, vec![Span::from(" "), yaml_scalar(scalar)])),
+    }
+}
+
+fn yaml_item(value: &Value, indent: usize, lines: &mut Vec<Line<'static>>) {
+    let dash = format!("{}- ", " ".repeat(indent));
+
+    match value {
+        Value::Array(items) if items.is_empty() => {
+            lines.push(Line::from(vec![Span::from(dash), Span::from("[]")]))
+        }
+        Value::Object(fields) if is_empty_object(fields) => {
+            lines.push(Line::from(vec![Span::from(dash), Span::from("{}")]))
+        }
+        Value::Array(_) | Value::Object(_) => {
+            // render the nested value one level deeper, then put the dash in
+            // front of its first line
+            let first = lines.len();
+            match value {
+                Value::Array(items) => {
+                    for item in items {
+                        yaml_item(item, indent + 2, lines);
+                    }
+                }
+                Value::Object(fields) => {
+                    for (key, field) in fields {
+                        yaml_field(key, field, indent + 2, lines);
+                    }
+                }
+                _ => {}
+            }
+            if let Some(span) = lines.get_mut(first).and_then(|line| line.spans.first_mut()) {
+                let rest = span
+                    .content
+                    .get(indent + 2..)
+                    .unwrap_or_default()
+                    .to_string();
+                *span = Span::from(format!("{dash}{rest}"));
+            }
+        }
+        scalar => lines.push(Line::from(vec![Span::from(dash), yaml_scalar(scalar)])),
+    }
+}
+
+// null fields are left out, an object of only those is shown as `{}`
+fn is_empty_object(fields: &[(String, Value)]) -> bool {
+    fields.iter().all(|(_, value)| *value == Value::Null)
+}
+
+fn yaml_scalar(value: &Value) -> Span<'static> {
+    match value {
+        Value::String(s) => {
+            let plain = !s.is_empty()
+                && !s.starts_with(|c: char| "-?:,[]{}#&*!|>'\"%@`~ ".contains(c))
+                && !s.ends_with(' ')
+                && !s.contains(": ")
+                && !s.contains(" #")
+                && !s.contains('\n')
+                && !matches!(s.as_str(), "true" | "false" | "null" | "yes" | "no")
+                && s.parse::<f64>().is_err();
+            scalar_span(value, !plain)
+        }
+        value => scalar_span(value, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> Value {
+        Value::from_json(
+            br#"{"taskDefinition": {"family": "web", "cpu": "256", "pidMode": null,
+                "containerDefinitions": [{"name": "web", "essential": true,
+                    "command": ["sh", "-c", "echo \"a, b\": c"],
+                    "portMappings": [{"containerPort": 80, "hostPort": null}, {"hostPort": null}],
+                    "dockerLabels": {"team": "web: api"}}],
+                "volumes": []}}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn renders_yaml() {
+        let lines = yaml_lines(&document())
+            .iter()
+            .map(plain)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            lines,
+            [
+                "taskDefinition:",
+                "  family: web",
+                "  cpu: \"256\"",
+                "  containerDefinitions:",
+                "  - name: web",
+                "    essential: true",
+                "    command:",
+                "    - sh",
+                "    - \"-c\"",
+                "    - \"echo \\\"a, b\\\": c\"",
+                "    portMappings:",
+                "    - containerPort: 80",
+                "    - {}",
+                "    dockerLabels:",
+                "      team: \"web: api\"",
+                "  volumes: []",
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_json() {
+        let mut lines = vec![];
+        json_value(&document(), 0, indentation(0), "", &mut lines);
+        let lines = lines.iter().map(plain).collect::<Vec<_>>();
+
+        assert_eq!(
+            lines,
+            [
+                "{",
+                "  \"taskDefinition\": {",
+                "    \"family\": \"web\",",
+                "    \"cpu\": \"256\",",
+                "    \"containerDefinitions\": [",
+                "      {",
+                "        \"name\": \"web\",",
+                "        \"essential\": true,",
+                "        \"command\": [",
+                "          \"sh\",",
+                "          \"-c\",",
+                "          \"echo \\\"a, b\\\": c\"",
+                "        ],",
+                "        \"portMappings\": [",
+                "          {",
+                "            \"containerPort\": 80",
+                "          },",
+                "          {}",
+                "        ],",
+                "        \"dockerLabels\": {",
+                "          \"team\": \"web: api\"",
+                "        }",
+                "      }",
+                "    ],",
+                "    \"volumes\": []",
+                "  }",
+                "}",
+            ]
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use ratatui::{
+    prelude::Stylize,
+    style::palette::tailwind,
+    text::{Line, Span},
+};
+
+// unchanged lines kept around each change
+const CONTEXT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Change {
+    Same,
+    Removed,
+    Added,
+}
+
+// line diff of `old` against `new`, only the changes and the lines around
+// them are kept, skipped lines are marked with "⋯"
+pub fn diff_lines(old: &[String], new: &[String]) -> Vec<Line<'static>> {
+    let changes = changes(old, new);
+    if changes.iter().all(|(change, _)| *change == Change::Same) {
+        return vec![Line::from("no differences").dark_gray().italic()];
+    }
+
+    // lines close enough to a change to be shown
+    let mut shown = vec![false; changes.len()];
+    for (i, (change, _)) in changes.iter().enumerate() {
+        if *change != Change::Same {
+            let start = i.saturating_sub(CONTEXT);
+            let end = (i + CONTEXT + 1).min(changes.len());
+            shown[start..end].iter_mut().for_each(|shown| *shown = true);
+        }
+    }
+
+    let mut lines = vec![];
+    for (i, (change, line)) in changes.into_iter().enumerate() {
+        if !shown[i] {
+            if i == 0 || shown[i - 1] {
+                lines.push(Line::from("⋯").dark_gray());
+            }
+            continue;
+        }
+        lines.push(match change {
+            Change::Same => Line::from(format!("  {line}")),
+            Change::Removed => Line::from(Span::from(format!("- {line}"))).fg(tailwind::RED.c400),
+            Change::Added => Line::from(Span::from(format!("+ {line}"))).fg(tailwind::GREEN.c400),
+        });
+    }
+    lines
+}
+
+// longest common subsequence of the lines, documents are at most a few
+// hundred lines so the quadratic table is fine
+fn changes<'a>(old: &'a [String], new: &'a [String]) -> Vec<(Change, &'a str)> {
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = match old[i] == new[j] {
+                true => common[i + 1][j + 1] + 1,
+                false => common[i + 1][j].max(common[i][j + 1]),
+            };
+        }
+    }
+
+    let mut changes = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            changes.push((Change::Same, old[i].as_str()));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            changes.push((Change::Removed, old[i].as_str()));
+            i += 1;
+        } else {
+            changes.push((Change::Added, new[j].as_str()));
+            j += 1;
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::describe::plain;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    fn diff(old: &str, new: &str) -> Vec<String> {
+        diff_lines(&lines(old), &lines(new))
+            .iter()
+            .map(plain)
+            .collect()
+    }
+
+    #[test]
+    fn reports_no_differences() {
+        assert_eq!(diff("a\nb", "a\nb"), ["no differences"]);
+        assert_eq!(diff("", ""), ["no differences"]);
+    }
+
+    #[test]
+    fn marks_removed_and_added_lines() {
+        assert_eq!(
+            diff(
+                "image: web:1\ncpu: 256",
+                "image: web:2\ncpu: 256\nmemory: 512"
+            ),
+            [
+                "- image: web:1",
+                "+ image: web:2",
+                "  cpu: 256",
+                "+ memory: 512",
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_lines_far_from_changes() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15";
+        let new = "1\n2\n3\n4\n5\n6\n7\nseven\n9\n10\n11\n12\n13\n14\n15";
+
+        assert_eq!(
+            diff(old, new),
+            ["⋯", "  5", "  6", "  7", "- 8", "+ seven", "  9", "  10", "  11", "⋯"]
+        );
+    }
+
+    #[test]
+    fn keeps_context_between_close_changes() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl";
+        let new = "A\nb\nc\nd\ne\nf\ng\nh\nI\nj\nk\nl";
+
+        // the changes are 8 lines apart, so the two lines between their
+        // context are skipped
+        assert_eq!(
+            diff(old, new),
+            [
+                "- a", "+ A", "  b", "  c", "  d", "⋯", "  f", "  g", "  h", "- i", "+ I", "  j",
+                "  k", "  l",
+            ]
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use aws_sdk_ecs::primitives::{DateTime, DateTimeFormat};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    layout::{Alignment, Constraint, Rect},
+    prelude::Stylize,
+    style::{palette::tailwind, Color, Modifier, Style},
+    widgets::{Block, Cell, Row, Table, TableState},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    route::{resource_id, DescribeTarget, Route},
+    state_store::{action::Action, ServiceEventItem, State},
+};
+
+use super::{
+    filter::Filter,
+    table::{age, clamp_selection, select_next, select_previous},
+    Component,
+};
+
+#[derive(Default)]
+struct Props {
+    events: Vec<ServiceEventItem>,
+}
+
+impl Props {
+    fn from_state(state: &State, service_arn: &str, filter: &Filter) -> Self {
+        let events = state
+            .service_events
+            .get(service_arn)
+            .into_iter()
+            .flatten()
+            .filter(|event| filter.matches(&[&event.message]))
+            .cloned()
+            .collect();
+
+        Self { events }
+    }
+}
+
+// events ECS records for a service, the first place placement failures and
+// failing health checks show up
+pub struct Events {
+    action_tx: Option<UnboundedSender<Action>>,
+    cluster_arn: String,
+    service_arn: String,
+    props: Props,
+    filter: Filter,
+    table_state: TableState,
+}
+
+impl Events {
+    pub fn new(cluster_arn: String, service_arn: String) -> Self {
+        Self {
+            action_tx: None,
+            cluster_arn,
+            service_arn,
+            props: Props::default(),
+            filter: Filter::default(),
+            table_state: TableState::default().with_selected(None),
+        }
+    }
+}
+
+// messages of tasks that could not be placed or failed their health checks
+fn severity(message: &str) -> Option<Color> {
+    let message = message.to_lowercase();
+    if message.contains("unable to place") {
+        Some(tailwind::RED.c400)
+    } else if message.contains("unhealthy") {
+        Some(tailwind::YELLOW.c300)
+    } else {
+        None
+    }
+}
+
+impl Component for Events {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn init(&mut self) -> Result<()> {
+        if let Some(tx) = self.action_tx.clone() {
+            tx.send(Action::GetServiceEvents {
+                cluster_arn: self.cluster_arn.clone(),
+                service_arn: self.service_arn.clone(),
+            })?;
+        }
+        Ok(())
+    }
+
+    fn captures_key(&self, key: &KeyEvent) -> bool {
+        self.filter.captures_key(key)
+    }
+
+    fn set_state(&mut self, state: &State) {
+        self.props = Props::from_state(state, &self.service_arn, &self.filter);
+        clamp_selection(&mut self.table_state, self.props.events.len());
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::Noop;
+        }
+
+        if self.filter.handle_key_event(key) {
+            return Action::Noop;
+        }
+
+        match key.code {
+            KeyCode::Char('k') => {
+                select_previous(&mut self.table_state, self.props.events.len());
+                Action::Noop
+            }
+            KeyCode::Char('j') => {
+                select_next(&mut self.table_state, self.props.events.len());
+                Action::Noop
+            }
+            // events are only described as part of their service
+            KeyCode::Char('d') => Action::Navigate(Route::Describe {
+                target: DescribeTarget::Service {
+                    cluster_arn: self.cluster_arn.clone(),
+                    service_arn: self.service_arn.clone(),
+                },
+            }),
+            _ => Action::Noop,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let selected_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(tailwind::CYAN.c100);
+
+        let header = ["TIME", "AGE", "MESSAGE"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .style(
+                Style::default()
+                    .bold()
+                    .bg(tailwind::CYAN.c200)
+                    .fg(tailwind::BLACK),
+            );
+        let rows = self.props.events.iter().map(|data| {
+            let item = [
+                // whole seconds, e.g. "2024-07-12T08:30:00Z"
+                data.created_at
+                    .and_then(|at| {
+                        DateTime::from_secs(at.secs())
+                            .fmt(DateTimeFormat::DateTime)
+                            .ok()
+                    })
+                    .unwrap_or_default(),
+                age(data.created_at.as_ref()),
+                data.message.clone(),
+            ];
+            let row = item
+                .into_iter()
+                .map(|content| Cell::from(self.filter.highlight(content)))
+                .collect::<Row>();
+            match severity(&data.message) {
+                Some(color) => row.fg(color),
+                None => row,
+            }
+        });
+
+        let t = Table::new(
+            rows,
+            [
+                Constraint::Length(20),
+                Constraint::Length(8),
+                Constraint::Min(40),
+            ],
+        )
+        .header(header)
+        .highlight_style(selected_style);
+
+        frame.render_stateful_widget(
+            t.block(
+                Block::bordered()
+                    .title(format!(
+                        " Events({})[{}] {}",
+                        resource_id(&self.service_arn),
+                        self.props.events.len(),
+                        self.filter.title()
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .style(Style::default().bold().fg(tailwind::CYAN.c200)),
+            ),
+            rect,
+            &mut self.table_state,
+        )
+    }
+}
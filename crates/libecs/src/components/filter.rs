@@ -0,0 +1,256 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    style::{palette::tailwind, Style},
+    text::{Line, Span},
+};
+use regex_lite::{Regex, RegexBuilder};
+
+// incremental row filter opened with `/`, the input is used as a case
+// insensitive regex and falls back to a plain substring, also case
+// insensitive, when it does not compile
+#[derive(Default)]
+pub struct Filter {
+    editing: bool,
+    input: String,
+    regex: Option<Regex>,
+}
+
+impl Filter {
+    pub fn is_active(&self) -> bool {
+        !self.input.is_empty()
+    }
+
+    // keys the filter needs before the app handles its global bindings
+    pub fn captures_key(&self, key: &KeyEvent) -> bool {
+        self.editing || (key.code == KeyCode::Esc && self.is_active())
+    }
+
+    // returns whether the key was consumed by the filter
+    pub fn handle_key_event(&mut self, key: KeyEvent) -> bool {
+        if key.kind != KeyEventKind::Press {
+            return false;
+        }
+
+        if !self.editing {
+            return match key.code {
+                KeyCode::Char('/') => {
+                    self.editing = true;
+                    true
+                }
+                KeyCode::Esc if self.is_active() => {
+                    self.set_input(String::new());
+                    true
+                }
+                _ => false,
+            };
+        }
+
+        match key.code {
+            KeyCode::Esc => {
+                self.editing = false;
+                self.set_input(String::new());
+            }
+            KeyCode::Enter => self.editing = false,
+            KeyCode::Backspace => {
+                let mut input = self.input.clone();
+                input.pop();
+                self.set_input(input);
+            }
+            KeyCode::Char(c) => {
+                let mut input = self.input.clone();
+                input.push(c);
+                self.set_input(input);
+            }
+            _ => {}
+        }
+
+        true
+    }
+
+    fn set_input(&mut self, input: String) {
+        self.regex = RegexBuilder::new(&input)
+            .case_insensitive(true)
+            .build()
+            .or_else(|_| {
+                RegexBuilder::new(&regex_lite::escape(&input))
+                    .case_insensitive(true)
+                    .build()
+            })
+            .ok();
+        self.input = input;
+    }
+
+    pub fn matches(&self, fields: &[&str]) -> bool {
+        !self.is_active()
+            || fields
+                .iter()
+                .any(|field| !self.match_ranges(field).is_empty())
+    }
+
+    fn match_ranges(&self, text: &str) -> Vec<(usize, usize)> {
+        if !self.is_active() {
+            return vec![];
+        }
+
+        match &self.regex {
+            Some(regex) => regex
+                .find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| (m.start(), m.end()))
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    pub fn highlight(&self, text: String) -> Line<'static> {
+        let ranges = self.match_ranges(&text);
+        if ranges.is_empty() {
+            return Line::from(text);
+        }
+
+        let highlight_style = Style::default()
+            .fg(tailwind::BLACK)
+            .bg(tailwind::YELLOW.c300);
+        let mut spans = vec![];
+        let mut last = 0;
+        for (start, end) in ranges {
+            spans.push(Span::from(text[last..start].to_string()));
+            spans.push(Span::styled(text[start..end].to_string(), highlight_style));
+            last = end;
+        }
+        spans.push(Span::from(text[last..].to_string()));
+
+        Line::from(spans)
+    }
+
+    // suffix appended to the table title, e.g. " </api> "
+    pub fn title(&self) -> String {
+        match (self.editing, self.is_active()) {
+            (true, _) => format!("</{}_> ", self.input),
+            (false, true) => format!("</{}> ", self.input),
+            (false, false) => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn typed(input: &str) -> Filter {
+        let mut filter = Filter::default();
+        filter.handle_key_event(key(KeyCode::Char('/')));
+        for c in input.chars() {
+            filter.handle_key_event(key(KeyCode::Char(c)));
+        }
+        filter.handle_key_event(key(KeyCode::Enter));
+        filter
+    }
+
+    fn spans(line: &Line) -> Vec<(String, bool)> {
+        line.spans
+            .iter()
+            .map(|span| (span.content.to_string(), span.style.bg.is_some()))
+            .collect()
+    }
+
+    #[test]
+    fn matches_any_field() {
+        let filter = typed("api");
+
+        assert!(filter.matches(&["web", "API-gateway"]));
+        assert!(!filter.matches(&["web", "worker"]));
+        assert!(Filter::default().matches(&["anything"]));
+    }
+
+    #[test]
+    fn matches_regex_ignoring_case() {
+        let filter = typed("^WEB-[0-9]+$");
+
+        assert!(filter.matches(&["web-12"]));
+        assert!(!filter.matches(&["web-12a"]));
+        assert!(!filter.matches(&["the web-12"]));
+    }
+
+    #[test]
+    fn falls_back_to_substring() {
+        // an unclosed class does not compile
+        let filter = typed("Web[");
+
+        assert!(filter.matches(&["web[1]"]));
+        assert!(!filter.matches(&["web1"]));
+    }
+
+    #[test]
+    fn captures_keys_while_editing() {
+        let mut filter = Filter::default();
+        assert!(!filter.captures_key(&key(KeyCode::Char('q'))));
+        assert!(!filter.captures_key(&key(KeyCode::Esc)));
+
+        assert!(filter.handle_key_event(key(KeyCode::Char('/'))));
+        assert!(filter.captures_key(&key(KeyCode::Char('q'))));
+        assert!(filter.captures_key(&key(KeyCode::Esc)));
+        assert_eq!(filter.title(), "</_> ");
+
+        filter.handle_key_event(key(KeyCode::Char('q')));
+        filter.handle_key_event(key(KeyCode::Char('x')));
+        filter.handle_key_event(key(KeyCode::Backspace));
+        assert_eq!(filter.title(), "</q_> ");
+
+        // escape while editing drops the input
+        filter.handle_key_event(key(KeyCode::Esc));
+        assert!(!filter.is_active());
+        assert_eq!(filter.title(), "");
+    }
+
+    #[test]
+    fn clears_active_filter_on_escape() {
+        let mut filter = typed("api");
+        assert_eq!(filter.title(), "</api> ");
+
+        // other keys go to the table again, escape clears the filter
+        // instead of going back
+        assert!(!filter.captures_key(&key(KeyCode::Char('j'))));
+        assert!(!filter.handle_key_event(key(KeyCode::Char('j'))));
+        assert!(filter.captures_key(&key(KeyCode::Esc)));
+        assert!(filter.handle_key_event(key(KeyCode::Esc)));
+
+        assert!(!filter.is_active());
+        assert!(!filter.captures_key(&key(KeyCode::Esc)));
+    }
+
+    #[test]
+    fn highlights_every_match() {
+        let filter = typed("web");
+
+        assert_eq!(
+            spans(&filter.highlight("Web-web-api".to_string())),
+            [
+                ("".to_string(), false),
+                ("Web".to_string(), true),
+                ("-".to_string(), false),
+                ("web".to_string(), true),
+                ("-api".to_string(), false),
+            ]
+        );
+        assert_eq!(
+            spans(&filter.highlight("api".to_string())),
+            [("api".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn skips_empty_matches() {
+        // matches the empty string everywhere
+        let filter = typed("x*");
+
+        assert!(!filter.matches(&["web"]));
+        assert!(filter.matches(&["box"]));
+    }
+}
@@ -0,0 +1,578 @@
+use std::collections::{HashMap, HashSet};
+
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Rect},
+    prelude::Stylize,
+    style::{palette::tailwind, Modifier, Style},
+    text::Line,
+    widgets::{Block, Cell, Paragraph, Row, Table, TableState},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    document::Value,
+    route::{resource_id, resource_name, DescribeTarget, Route},
+    state_store::{action::Action, DeploymentItem, State, TaskDefinitionItem},
+};
+
+use super::{
+    confirm::Confirm,
+    describe::{plain, yaml_lines},
+    diff::diff_lines,
+    table::{age, clamp_selection, next_index, previous_index},
+    Component,
+};
+
+// fields that differ between any two revisions, left out of the diff
+const REVISION_FIELDS: [&str; 6] = [
+    "taskDefinitionArn",
+    "revision",
+    "status",
+    "registeredAt",
+    "registeredBy",
+    "deregisteredAt",
+];
+
+#[derive(Default)]
+struct Props {
+    deployments: Vec<DeploymentItem>,
+    revisions: Vec<TaskDefinitionItem>,
+    // ARN of the revision the service runs
+    current: Option<String>,
+    // described revisions keyed by ARN
+    documents: HashMap<String, Value>,
+}
+
+impl Props {
+    fn from_state(state: &State, cluster_arn: &str, service_arn: &str) -> Self {
+        let revisions = state
+            .revisions
+            .get(service_arn)
+            .cloned()
+            .unwrap_or_default();
+        // "<family>:<revision>"
+        let task_definition = state
+            .services
+            .get(cluster_arn)
+            .into_iter()
+            .flatten()
+            .find(|service| service.arn == service_arn)
+            .map(|service| service.task_definition.clone());
+        let current = revisions
+            .iter()
+            .find(|item| Some(format!("{}:{}", item.family, item.revision)) == task_definition)
+            .map(|item| item.arn.clone());
+        let documents = revisions
+            .iter()
+            .filter_map(|item| {
+                let target = DescribeTarget::TaskDefinition {
+                    task_definition_arn: item.arn.clone(),
+                };
+                state
+                    .descriptions
+                    .get(&target)
+                    .map(|value| (item.arn.clone(), value.clone()))
+            })
+            .collect();
+
+        Self {
+            deployments: state
+                .deployments
+                .get(service_arn)
+                .cloned()
+                .unwrap_or_default(),
+            revisions,
+            current,
+            documents,
+        }
+    }
+}
+
+// deployments of a service next to the last revisions of its task
+// definition, the selected revision is diffed against the running one and
+// can be rolled back to
+pub struct History {
+    action_tx: Option<UnboundedSender<Action>>,
+    cluster_arn: String,
+    service_arn: String,
+    props: Props,
+    table_state: TableState,
+    // revisions whose description was asked for
+    requested: HashSet<String>,
+    // (current, selected) ARNs the diff was computed for
+    diffed: Option<(String, String)>,
+    diff: Vec<Line<'static>>,
+    scroll: usize,
+    confirm: Option<Confirm>,
+}
+
+impl History {
+    pub fn new(cluster_arn: String, service_arn: String) -> Self {
+        Self {
+            action_tx: None,
+            cluster_arn,
+            service_arn,
+            props: Props::default(),
+            table_state: TableState::default().with_selected(None),
+            requested: HashSet::new(),
+            diffed: None,
+            diff: vec![],
+            scroll: 0,
+            confirm: None,
+        }
+    }
+
+    fn selected(&self) -> Option<&TaskDefinitionItem> {
+        self.table_state
+            .selected()
+            .and_then(|i| self.props.revisions.get(i))
+    }
+
+    // the running revision and the selected one are described once
+    fn request_documents(&mut self) {
+        let Some(tx) = self.action_tx.clone() else {
+            return;
+        };
+        let arns = [
+            self.props.current.clone(),
+            self.selected().map(|item| item.arn.clone()),
+        ];
+        for arn in arns.into_iter().flatten() {
+            if !self.props.documents.contains_key(&arn) && self.requested.insert(arn.clone()) {
+                let _ = tx.send(Action::Describe(DescribeTarget::TaskDefinition {
+                    task_definition_arn: arn,
+                }));
+            }
+        }
+    }
+
+    fn update_diff(&mut self) {
+        let (Some(current), Some(selected)) = (
+            self.props.current.clone(),
+            self.selected().map(|item| item.arn.clone()),
+        ) else {
+            self.diffed = None;
+            self.diff.clear();
+            return;
+        };
+        let key = (current.clone(), selected.clone());
+        if self.diffed.as_ref() == Some(&key) {
+            return;
+        }
+
+        let (Some(old), Some(new)) = (
+            self.props.documents.get(&current).map(document_lines),
+            self.props.documents.get(&selected).map(document_lines),
+        ) else {
+            self.diffed = None;
+            self.diff = vec![Line::from("loading…").dark_gray().italic()];
+            return;
+        };
+        self.diff = diff_lines(&old, &new);
+        self.diffed = Some(key);
+        self.scroll = 0;
+    }
+
+    fn select(&mut self, i: usize) {
+        self.table_state.select(Some(i));
+        self.request_documents();
+        self.update_diff();
+    }
+
+    fn next(&mut self) {
+        if let Some(i) = next_index(self.table_state.selected(), self.props.revisions.len()) {
+            self.select(i);
+        }
+    }
+
+    fn previous(&mut self) {
+        if let Some(i) = previous_index(self.table_state.selected(), self.props.revisions.len()) {
+            self.select(i);
+        }
+    }
+
+    // `ctrl-r` updates the service to the selected revision, its rollout is
+    // shown once confirmed
+    fn open_rollback(&mut self) {
+        let (Some(selected), Some(current)) = (self.selected(), &self.props.current) else {
+            return;
+        };
+        if &selected.arn == current {
+            return;
+        }
+
+        let cluster_arn = self.cluster_arn.clone();
+        let service_arn = self.service_arn.clone();
+        let task_definition_arn = selected.arn.clone();
+        self.confirm = Some(Confirm::new(
+            "Roll back service",
+            vec![
+                (
+                    "service".to_string(),
+                    resource_id(&self.service_arn).to_string(),
+                ),
+                ("running".to_string(), resource_name(current).to_string()),
+                (
+                    "roll back to".to_string(),
+                    resource_name(&selected.arn).to_string(),
+                ),
+            ],
+            move |_| {
+                Ok(Action::RollbackService {
+                    cluster_arn: cluster_arn.clone(),
+                    service_arn: service_arn.clone(),
+                    task_definition_arn: task_definition_arn.clone(),
+                })
+            },
+        ));
+    }
+
+    fn draw_deployments(&self, frame: &mut Frame, rect: Rect) {
+        let header = [
+            "STATUS",
+            "TASK DEFINITION",
+            "DESIRED",
+            "RUNNING",
+            "FAILED",
+            "ROLLOUT",
+            "UPDATED",
+        ]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(
+            Style::default()
+                .bold()
+                .bg(tailwind::CYAN.c200)
+                .fg(tailwind::BLACK),
+        );
+        let rows = self.props.deployments.iter().map(|data| {
+            Row::new([
+                data.status.clone(),
+                data.task_definition.clone(),
+                data.desired_count.to_string(),
+                data.running_count.to_string(),
+                data.failed_tasks.to_string(),
+                data.rollout_state.clone().unwrap_or_default(),
+                age(data.updated_at.as_ref()),
+            ])
+        });
+
+        let t = Table::new(
+            rows,
+            [
+                Constraint::Length(9),
+                Constraint::Min(20),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(7),
+                Constraint::Length(12),
+                Constraint::Length(8),
+            ],
+        )
+        .header(header);
+
+        frame.render_widget(
+            t.block(
+                Block::bordered()
+                    .title(format!(
+                        " Deployments({})[{}] ",
+                        resource_id(&self.service_arn),
+                        self.props.deployments.len()
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .style(Style::default().bold().fg(tailwind::CYAN.c200)),
+            ),
+            rect,
+        );
+    }
+
+    fn draw_revisions(&mut self, frame: &mut Frame, rect: Rect) {
+        let selected_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(tailwind::CYAN.c100);
+
+        let header = ["REVISION", ""]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .style(
+                Style::default()
+                    .bold()
+                    .bg(tailwind::CYAN.c200)
+                    .fg(tailwind::BLACK),
+            );
+        let rows = self.props.revisions.iter().map(|data| {
+            let current = Some(&data.arn) == self.props.current.as_ref();
+            let row = Row::new([
+                format!("{}:{}", data.family, data.revision),
+                match current {
+                    true => "running".to_string(),
+                    false => String::new(),
+                },
+            ]);
+            match current {
+                true => row.fg(tailwind::GREEN.c400),
+                false => row,
+            }
+        });
+
+        let family = self
+            .props
+            .revisions
+            .first()
+            .map(|item| item.family.clone())
+            .unwrap_or_default();
+        let t = Table::new(rows, [Constraint::Min(16), Constraint::Length(8)])
+            .header(header)
+            .highlight_style(selected_style);
+
+        frame.render_stateful_widget(
+            t.block(
+                Block::bordered()
+                    .title(format!(
+                        " Revisions({family})[{}] ",
+                        self.props.revisions.len()
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .style(Style::default().bold().fg(tailwind::CYAN.c200)),
+            ),
+            rect,
+            &mut self.table_state,
+        );
+    }
+
+    fn draw_diff(&self, frame: &mut Frame, rect: Rect) {
+        let title = match (&self.props.current, self.selected()) {
+            (Some(current), Some(selected)) => format!(
+                " Diff({} → {}) ",
+                resource_name(current),
+                resource_name(&selected.arn)
+            ),
+            _ => " Diff ".to_string(),
+        };
+        let lines = self
+            .diff
+            .iter()
+            .skip(self.scroll)
+            .take(rect.height as usize)
+            .cloned()
+            .collect::<Vec<Line>>();
+
+        frame.render_widget(
+            Paragraph::new(lines).block(
+                Block::bordered()
+                    .title(title)
+                    .title_alignment(Alignment::Center)
+                    .style(Style::default().bold().fg(tailwind::CYAN.c200)),
+            ),
+            rect,
+        );
+    }
+}
+
+impl Component for History {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn init(&mut self) -> Result<()> {
+        if let Some(tx) = self.action_tx.clone() {
+            tx.send(Action::GetRevisions {
+                cluster_arn: self.cluster_arn.clone(),
+                service_arn: self.service_arn.clone(),
+            })?;
+        }
+        Ok(())
+    }
+
+    fn captures_key(&self, _key: &KeyEvent) -> bool {
+        self.confirm.is_some()
+    }
+
+    fn set_state(&mut self, state: &State) {
+        self.props = Props::from_state(state, &self.cluster_arn, &self.service_arn);
+        clamp_selection(&mut self.table_state, self.props.revisions.len());
+        // the revision before the running one is the usual rollback target
+        if self.table_state.selected().is_none() && self.props.current.is_some() {
+            let previous = self
+                .props
+                .revisions
+                .iter()
+                .position(|item| Some(&item.arn) == self.props.current.as_ref())
+                .map(|i| (i + 1).min(self.props.revisions.len() - 1));
+            self.table_state.select(previous);
+        }
+        self.request_documents();
+        self.update_diff();
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::Noop;
+        }
+
+        if let Some(confirm) = &mut self.confirm {
+            let action = confirm.handle_key_event(key);
+            if !confirm.is_closed() {
+                return action;
+            }
+            self.confirm = None;
+            let Action::RollbackService {
+                cluster_arn,
+                service_arn,
+                ..
+            } = &action
+            else {
+                return action;
+            };
+            let route = Route::Deployments {
+                cluster_arn: cluster_arn.clone(),
+                service_arn: service_arn.clone(),
+            };
+            if let Some(tx) = &self.action_tx {
+                let _ = tx.send(action);
+            }
+            return Action::Navigate(route);
+        }
+
+        let last = self.diff.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.open_rollback()
+            }
+            KeyCode::Char('k') => self.previous(),
+            KeyCode::Char('j') => self.next(),
+            KeyCode::Char('J') | KeyCode::PageDown => self.scroll = (self.scroll + 10).min(last),
+            KeyCode::Char('K') | KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::Char('d') => {
+                if let Some(selected) = self.selected() {
+                    return Action::Navigate(Route::Describe {
+                        target: DescribeTarget::TaskDefinition {
+                            task_definition_arn: selected.arn.clone(),
+                        },
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        Action::Noop
+    }
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let deployments_height = self.props.deployments.len().clamp(1, 4) as u16 + 3;
+        let [deployments_area, revisions_area] =
+            Layout::vertical([Constraint::Length(deployments_height), Constraint::Min(0)])
+                .areas(rect);
+        let [list_area, diff_area] =
+            Layout::horizontal([Constraint::Length(30), Constraint::Min(0)]).areas(revisions_area);
+
+        self.draw_deployments(frame, deployments_area);
+        self.draw_revisions(frame, list_area);
+        self.draw_diff(frame, diff_area);
+
+        if let Some(confirm) = &mut self.confirm {
+            let confirm_area = confirm.area(rect);
+            confirm.draw(frame, confirm_area);
+        }
+    }
+}
+
+// YAML lines of the task definition, without the fields of the revision
+// itself
+fn document_lines(value: &Value) -> Vec<String> {
+    let task_definition = match value {
+        Value::Object(fields) => fields
+            .iter()
+            .find(|(key, _)| key == "taskDefinition")
+            .map(|(_, value)| value),
+        _ => None,
+    };
+    let task_definition = match task_definition {
+        Some(Value::Object(fields)) => Value::Object(
+            fields
+                .iter()
+                .filter(|(key, _)| !REVISION_FIELDS.contains(&key.as_str()))
+                .cloned()
+                .collect(),
+        ),
+        _ => value.clone(),
+    };
+    yaml_lines(&task_definition).iter().map(plain).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn revision(revision: u32, image: &str) -> Value {
+        Value::from_json(
+            format!(
+                r#"{{"taskDefinition": {{
+                    "taskDefinitionArn": "arn:aws:ecs:us-east-1:123456789012:task-definition/web:{revision}",
+                    "containerDefinitions": [{{"name": "web", "image": "{image}",
+                        "command": ["sh", "-c", "serve"]}}],
+                    "family": "web", "revision": {revision}, "status": "ACTIVE",
+                    "cpu": "256", "registeredAt": 1.7200512E9,
+                    "registeredBy": "arn:aws:iam::123456789012:root"}}, "tags": []}}"#
+            )
+            .as_bytes(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn renders_task_definition_without_revision_fields() {
+        assert_eq!(
+            document_lines(&revision(3, "web:1.0")),
+            [
+                "containerDefinitions:",
+                "- name: web",
+                "  image: web:1.0",
+                "  command:",
+                "  - sh",
+                "  - \"-c\"",
+                "  - serve",
+                "family: web",
+                "cpu: \"256\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_whole_document_without_task_definition() {
+        let value = Value::from_json(br#"{"failures": []}"#).unwrap();
+
+        assert_eq!(document_lines(&value), ["failures: []"]);
+    }
+
+    #[test]
+    fn diffs_only_the_changed_fields() {
+        let old = document_lines(&revision(3, "web:1.0"));
+        let new = document_lines(&revision(4, "web:1.1"));
+
+        let diff: Vec<String> = diff_lines(&old, &new).iter().map(plain).collect();
+        assert_eq!(
+            diff,
+            [
+                "  containerDefinitions:",
+                "  - name: web",
+                "-   image: web:1.0",
+                "+   image: web:1.1",
+                "    command:",
+                "    - sh",
+                "    - \"-c\"",
+                "⋯",
+            ]
+        );
+
+        let same = document_lines(&revision(4, "web:1.0"));
+        let diff: Vec<String> = diff_lines(&old, &same).iter().map(plain).collect();
+        assert_eq!(diff, ["no differences"]);
+    }
+}
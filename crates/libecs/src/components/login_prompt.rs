@@ -0,0 +1,150 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    layout::{Alignment, Rect},
+    prelude::Stylize,
+    style::{palette::tailwind, Style},
+    text::{Line, Span},
+    widgets::{
+        block::{Position, Title},
+        Block, Clear, Paragraph, Wrap,
+    },
+    Frame,
+};
+
+use crate::state_store::{action::Action, CredentialPrompt, State};
+
+use super::Component;
+
+// modal opened by the store when a call needs an MFA token code or a new
+// SSO sign-in, the failed calls are retried once it closes
+#[derive(Default)]
+pub struct LoginPrompt {
+    prompt: Option<CredentialPrompt>,
+    input: String,
+    message: Option<String>,
+}
+
+impl LoginPrompt {
+    pub fn is_active(&self) -> bool {
+        self.prompt.is_some()
+    }
+
+    // centered over the content area
+    pub fn area(&self, rect: Rect) -> Rect {
+        let width = 70.min(rect.width);
+        let height = 8.min(rect.height);
+        Rect::new(
+            rect.x + (rect.width - width) / 2,
+            rect.y + (rect.height - height) / 2,
+            width,
+            height,
+        )
+    }
+
+    fn submit(&mut self) -> Action {
+        if self.input.len() != 6 {
+            self.message = Some("the token code has 6 digits".to_string());
+            return Action::Noop;
+        }
+        Action::SubmitMfaCode {
+            code: std::mem::take(&mut self.input),
+        }
+    }
+}
+
+impl Component for LoginPrompt {
+    fn set_state(&mut self, state: &State) {
+        if self.prompt != state.credential_prompt {
+            self.message = match &state.credential_prompt {
+                Some(CredentialPrompt::Mfa { error, .. }) => error.clone(),
+                _ => None,
+            };
+            if state.credential_prompt.is_none() {
+                self.input.clear();
+            }
+        }
+        self.prompt = state.credential_prompt.clone();
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::Noop;
+        }
+
+        match (&self.prompt, key.code) {
+            (_, KeyCode::Esc) => Action::CancelLogin,
+            (Some(CredentialPrompt::Mfa { .. }), KeyCode::Enter) => self.submit(),
+            (Some(CredentialPrompt::Mfa { .. }), KeyCode::Backspace) => {
+                self.input.pop();
+                self.message = None;
+                Action::Noop
+            }
+            (Some(CredentialPrompt::Mfa { .. }), KeyCode::Char(c))
+                if c.is_ascii_digit() && self.input.len() < 6 =>
+            {
+                self.input.push(c);
+                self.message = None;
+                Action::Noop
+            }
+            _ => Action::Noop,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let Some(prompt) = &self.prompt else {
+            return;
+        };
+
+        let (title, keys, mut lines) = match prompt {
+            CredentialPrompt::Mfa { name, serial, .. } => (
+                " MFA code ",
+                " <enter> Submit <esc> Cancel ",
+                vec![
+                    Line::from(format!("{name} needs a token code of")).dark_gray(),
+                    Line::from(serial.clone()).dark_gray(),
+                    Line::default(),
+                    Line::from(vec![
+                        Span::from("code: "),
+                        Span::from(format!("{}_", self.input)).fg(tailwind::CYAN.c100),
+                    ]),
+                ],
+            ),
+            CredentialPrompt::Sso {
+                profile,
+                verification_uri,
+                user_code,
+            } => (
+                " SSO sign-in ",
+                " <esc> Cancel ",
+                vec![
+                    Line::from(format!("the SSO session of {profile} expired, open")).dark_gray(),
+                    Line::from(verification_uri.clone()).fg(tailwind::CYAN.c100),
+                    Line::from(vec![
+                        Span::from("and confirm the code ").dark_gray(),
+                        Span::from(user_code.clone()).fg(tailwind::CYAN.c100),
+                    ]),
+                    Line::from("waiting for the sign-in…").dark_gray().italic(),
+                ],
+            ),
+        };
+        if let Some(message) = &self.message {
+            lines.push(Line::from(message.clone()).fg(tailwind::RED.c400));
+        }
+
+        frame.render_widget(Clear, rect);
+        frame.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+                Block::bordered()
+                    .title(title)
+                    .title(
+                        Title::from(keys)
+                            .position(Position::Bottom)
+                            .alignment(Alignment::Right),
+                    )
+                    .title_alignment(Alignment::Center)
+                    .style(Style::default().bold().fg(tailwind::CYAN.c200)),
+            ),
+            rect,
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use aws_sdk_ecs::primitives::{DateTime, DateTimeFormat};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    layout::{Alignment, Rect},
+    prelude::Stylize,
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{
+        block::{Position, Title},
+        Block, Paragraph,
+    },
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::state_store::{action::Action, ContainerLogs, LogKey, State};
+
+use super::{filter::Filter, Component};
+
+// awslogs stream of a container, polled again on every refresh, `f` keeps
+// the newest events in view
+pub struct Logs {
+    action_tx: Option<UnboundedSender<Action>>,
+    cluster_arn: String,
+    key: LogKey,
+    logs: ContainerLogs,
+    search: Filter,
+    // first event shown when not following
+    scroll: usize,
+    follow: bool,
+    wrap: bool,
+    timestamps: bool,
+    // first event and rows shown by the last draw, scrolling starts there
+    top: usize,
+    height: usize,
+}
+
+impl Logs {
+    pub fn new(cluster_arn: String, task_arn: String, container_name: String) -> Self {
+        Self {
+            action_tx: None,
+            cluster_arn,
+            key: LogKey {
+                task_arn,
+                container_name,
+            },
+            logs: ContainerLogs::default(),
+            search: Filter::default(),
+            scroll: 0,
+            follow: true,
+            wrap: false,
+            timestamps: true,
+            top: 0,
+            height: 0,
+        }
+    }
+
+    fn text(&self, i: usize) -> String {
+        let event = &self.logs.events[i];
+        match (self.timestamps, event.timestamp) {
+            (true, Some(timestamp)) => format!(
+                "{} {}",
+                DateTime::from_secs(timestamp / 1000)
+                    .fmt(DateTimeFormat::DateTime)
+                    .unwrap_or_default(),
+                event.message
+            ),
+            _ => event.message.clone(),
+        }
+    }
+
+    // rows the event takes in a pane `width` columns wide
+    fn rows(&self, i: usize, width: usize) -> Vec<String> {
+        let text = self.text(i);
+        if !self.wrap || width == 0 {
+            return vec![text];
+        }
+        let chars = text.chars().collect::<Vec<_>>();
+        match chars.is_empty() {
+            true => vec![String::new()],
+            false => chars
+                .chunks(width)
+                .map(|chunk| chunk.iter().collect())
+                .collect(),
+        }
+    }
+
+    // first event of a view that ends with the newest one
+    fn bottom(&self, width: usize, height: usize) -> usize {
+        let mut rows = 0;
+        for i in (0..self.logs.events.len()).rev() {
+            rows += self.rows(i, width).len();
+            if rows > height {
+                return i + 1;
+            }
+        }
+        0
+    }
+
+    fn is_match(&self, i: usize) -> bool {
+        self.search.is_active() && self.search.matches(&[&self.text(i)])
+    }
+
+    fn scroll_to(&mut self, i: usize) {
+        self.follow = false;
+        self.scroll = i.min(self.logs.events.len().saturating_sub(1));
+    }
+
+    fn next_match(&mut self) {
+        if let Some(i) = (self.top + 1..self.logs.events.len()).find(|&i| self.is_match(i)) {
+            self.scroll_to(i);
+        }
+    }
+
+    fn previous_match(&mut self) {
+        if let Some(i) = (0..self.top).rev().find(|&i| self.is_match(i)) {
+            self.scroll_to(i);
+        }
+    }
+
+    fn flags(&self) -> String {
+        [
+            (self.follow, "follow"),
+            (self.wrap, "wrap"),
+            (self.timestamps, "time"),
+        ]
+        .into_iter()
+        .filter_map(|(on, flag)| on.then_some(flag))
+        .collect::<Vec<_>>()
+        .join(" ")
+    }
+}
+
+impl Component for Logs {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn init(&mut self) -> Result<()> {
+        if let Some(tx) = self.action_tx.clone() {
+            tx.send(Action::GetLogEvents {
+                cluster_arn: self.cluster_arn.clone(),
+                task_arn: self.key.task_arn.clone(),
+                container_name: self.key.container_name.clone(),
+            })?;
+        }
+        Ok(())
+    }
+
+    fn captures_key(&self, key: &KeyEvent) -> bool {
+        self.search.captures_key(key)
+    }
+
+    fn set_state(&mut self, state: &State) {
+        if let Some(logs) = state.logs.get(&self.key) {
+            self.logs = logs.clone();
+        }
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::Noop;
+        }
+
+        if self.search.handle_key_event(key) {
+            // jump to the first match while typing
+            if let Some(i) = (0..self.logs.events.len()).find(|&i| self.is_match(i)) {
+                self.scroll_to(i);
+            }
+            return Action::Noop;
+        }
+
+        let page = self.height.max(1);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.scroll_to(self.top + 1),
+            KeyCode::Char('k') | KeyCode::Up => self.scroll_to(self.top.saturating_sub(1)),
+            KeyCode::PageDown => self.scroll_to(self.top + page),
+            KeyCode::PageUp => self.scroll_to(self.top.saturating_sub(page)),
+            KeyCode::Char('g') => self.scroll_to(0),
+            KeyCode::Char('G') => self.follow = true,
+            KeyCode::Char('f') => {
+                self.scroll = self.top;
+                self.follow = !self.follow;
+            }
+            KeyCode::Char('w') => self.wrap = !self.wrap,
+            KeyCode::Char('t') => self.timestamps = !self.timestamps,
+            KeyCode::Char('n') => self.next_match(),
+            KeyCode::Char('N') => self.previous_match(),
+            _ => {}
+        }
+
+        Action::Noop
+    }
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let width = rect.width.saturating_sub(2) as usize;
+        let height = rect.height.saturating_sub(2) as usize;
+
+        let top = match self.follow {
+            true => self.bottom(width, height),
+            false => self.scroll.min(self.logs.events.len().saturating_sub(1)),
+        };
+        let mut lines = vec![];
+        for i in top..self.logs.events.len() {
+            if lines.len() >= height {
+                break;
+            }
+            let matched = self.is_match(i);
+            for row in self.rows(i, width) {
+                lines.push(match matched {
+                    true => self.search.highlight(row),
+                    false => Line::from(row),
+                });
+            }
+        }
+        lines.truncate(height);
+        if lines.is_empty() {
+            let placeholder = match (&self.logs.unavailable, &self.logs.source) {
+                (Some(unavailable), _) => Line::from(unavailable.clone()).fg(tailwind::RED.c400),
+                (None, Some(_)) => Line::from("no events yet").dark_gray().italic(),
+                (None, None) => Line::from("loading…").dark_gray().italic(),
+            };
+            lines.push(placeholder);
+        }
+        self.top = top;
+        self.height = height;
+
+        let mut block = Block::bordered()
+            .title(format!(
+                " Logs({})[{}] <{}> {}",
+                self.key.container_name,
+                self.logs.events.len(),
+                self.flags(),
+                self.search.title()
+            ))
+            .title_alignment(Alignment::Center)
+            .style(Style::default().bold().fg(tailwind::CYAN.c200));
+        if let Some(source) = &self.logs.source {
+            block = block.title(
+                Title::from(format!(" {source} "))
+                    .position(Position::Bottom)
+                    .alignment(Alignment::Left),
+            );
+        }
+        frame.render_widget(Paragraph::new(lines).block(block), rect);
+    }
+}
@@ -1,5 +1,23 @@
 mod component;
+mod diff;
+mod filter;
+mod table;
 
 pub mod clusters;
+pub mod command;
+pub mod confirm;
+pub mod container_instances;
+pub mod containers;
 pub mod context;
+pub mod context_switcher;
+pub mod deployments;
+pub mod describe;
+pub mod events;
+pub mod history;
+pub mod login_prompt;
+pub mod logs;
+pub mod services;
+pub mod task_definitions;
+pub mod tasks;
+pub mod toast;
 pub use component::{Component, Event};
@@ -0,0 +1,526 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Rect},
+    prelude::Stylize,
+    style::{palette::tailwind, Modifier, Style},
+    widgets::{Block, Cell, Row, Table, TableState},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    route::{resource_name, DescribeTarget, Route},
+    state_store::{action::Action, Listing, ServiceItem, State},
+};
+
+use super::{
+    confirm::Confirm,
+    filter::Filter,
+    table::{clamp_selection, loading_footer, select_next, select_previous},
+    Component,
+};
+
+#[derive(Default)]
+struct Props {
+    services: Vec<ServiceItem>,
+    loading: bool,
+    // services scaled from the app that have not converged yet
+    scaling: Vec<String>,
+}
+
+impl Props {
+    fn from_state(state: &State, cluster_arn: &str, filter: &Filter) -> Self {
+        let services = state
+            .services
+            .get(cluster_arn)
+            .into_iter()
+            .flatten()
+            .filter(|service| {
+                filter.matches(&[&service.name, &service.status, &service.task_definition])
+            })
+            .cloned()
+            .collect();
+
+        Self {
+            services,
+            loading: state.loading.contains(&Listing::Services {
+                cluster_arn: cluster_arn.to_string(),
+            }),
+            scaling: state.scaling.iter().cloned().collect(),
+        }
+    }
+}
+
+pub struct Services {
+    action_tx: Option<UnboundedSender<Action>>,
+    cluster_arn: String,
+    props: Props,
+    filter: Filter,
+    table_state: TableState,
+    confirm: Option<Confirm>,
+    // the open dialog is the second one of a large scale, its action is sent
+    // as is
+    confirming_jump: bool,
+    scale_confirm_above: i32,
+}
+
+impl Services {
+    pub fn new(cluster_arn: String, scale_confirm_above: i32) -> Self {
+        Self {
+            action_tx: None,
+            cluster_arn,
+            props: Props::default(),
+            filter: Filter::default(),
+            table_state: TableState::default().with_selected(None),
+            confirm: None,
+            confirming_jump: false,
+            scale_confirm_above,
+        }
+    }
+
+    // `ctrl-s` asks for the new desired count of the selected service, it
+    // has to stay within the auto scaling range when one is registered
+    fn open_scale(&mut self) {
+        let Some(service) = self
+            .table_state
+            .selected()
+            .and_then(|i| self.props.services.get(i))
+        else {
+            return;
+        };
+
+        let scaling_range = service.scaling_range;
+        let range = match scaling_range {
+            Some((min, max)) => format!("min {min} / max {max}"),
+            None => "not registered".to_string(),
+        };
+        let cluster_arn = self.cluster_arn.clone();
+        let service_arn = service.arn.clone();
+        self.confirm = Some(
+            Confirm::new(
+                "Scale service",
+                vec![
+                    ("service".to_string(), service.name.clone()),
+                    ("desired".to_string(), service.desired_count.to_string()),
+                    ("running".to_string(), service.running_count.to_string()),
+                    ("pending".to_string(), service.pending_count.to_string()),
+                    ("auto scaling".to_string(), range),
+                ],
+                move |input| {
+                    let desired_count = input
+                        .parse::<i32>()
+                        .ok()
+                        .filter(|count| *count >= 0)
+                        .ok_or("the desired count is a whole number from 0")?;
+                    if let Some((min, max)) = scaling_range {
+                        if !(min..=max).contains(&desired_count) {
+                            return Err(format!(
+                                "{desired_count} is outside the auto scaling range {min}-{max}"
+                            ));
+                        }
+                    }
+                    Ok(Action::ScaleService {
+                        cluster_arn: cluster_arn.clone(),
+                        service_arn: service_arn.clone(),
+                        desired_count,
+                    })
+                },
+            )
+            .with_input("desired count", service.desired_count.to_string()),
+        );
+    }
+
+    // `r` restarts the tasks of the selected service with a forced
+    // deployment, its rollout is shown once confirmed
+    fn open_redeploy(&mut self) {
+        let Some(service) = self
+            .table_state
+            .selected()
+            .and_then(|i| self.props.services.get(i))
+        else {
+            return;
+        };
+
+        let cluster_arn = self.cluster_arn.clone();
+        let service_arn = service.arn.clone();
+        self.confirm = Some(Confirm::new(
+            "Force new deployment",
+            vec![
+                ("service".to_string(), service.name.clone()),
+                (
+                    "task definition".to_string(),
+                    service.task_definition.clone(),
+                ),
+                ("desired".to_string(), service.desired_count.to_string()),
+                ("running".to_string(), service.running_count.to_string()),
+            ],
+            move |_| {
+                Ok(Action::ForceDeployment {
+                    cluster_arn: cluster_arn.clone(),
+                    service_arn: service_arn.clone(),
+                })
+            },
+        ));
+    }
+
+    fn confirmed(&mut self, action: Action) -> Action {
+        match action {
+            Action::ScaleService { .. } => self.confirm_jump(action),
+            Action::ForceDeployment {
+                cluster_arn,
+                service_arn,
+            } => {
+                if let Some(tx) = &self.action_tx {
+                    let _ = tx.send(Action::ForceDeployment {
+                        cluster_arn: cluster_arn.clone(),
+                        service_arn: service_arn.clone(),
+                    });
+                }
+                Action::Navigate(Route::Deployments {
+                    cluster_arn,
+                    service_arn,
+                })
+            }
+            action => action,
+        }
+    }
+
+    // a large jump is confirmed once more before the service is updated
+    fn confirm_jump(&mut self, action: Action) -> Action {
+        let Action::ScaleService {
+            service_arn,
+            desired_count,
+            ..
+        } = &action
+        else {
+            return action;
+        };
+        let Some(service) = self
+            .props
+            .services
+            .iter()
+            .find(|service| &service.arn == service_arn)
+        else {
+            return action;
+        };
+
+        let jump = (desired_count - service.desired_count).abs();
+        if jump <= self.scale_confirm_above {
+            return action;
+        }
+        self.confirm = Some(Confirm::new(
+            "Confirm large scale",
+            vec![
+                ("service".to_string(), service.name.clone()),
+                (
+                    "desired".to_string(),
+                    format!("{} -> {desired_count}", service.desired_count),
+                ),
+                (
+                    "jump".to_string(),
+                    format!("{jump} tasks, above {}", self.scale_confirm_above),
+                ),
+            ],
+            move |_| Ok(action.clone()),
+        ));
+        self.confirming_jump = true;
+        Action::Noop
+    }
+}
+
+impl Component for Services {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn init(&mut self) -> Result<()> {
+        if let Some(tx) = self.action_tx.clone() {
+            tx.send(Action::GetServices {
+                cluster_arn: self.cluster_arn.clone(),
+            })?;
+        }
+        Ok(())
+    }
+
+    fn captures_key(&self, key: &KeyEvent) -> bool {
+        self.confirm.is_some() || self.filter.captures_key(key)
+    }
+
+    fn set_state(&mut self, state: &State) {
+        self.props = Props::from_state(state, &self.cluster_arn, &self.filter);
+        clamp_selection(&mut self.table_state, self.props.services.len());
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::Noop;
+        }
+
+        if let Some(confirm) = &mut self.confirm {
+            let action = confirm.handle_key_event(key);
+            if !confirm.is_closed() {
+                return action;
+            }
+            self.confirm = None;
+            if std::mem::take(&mut self.confirming_jump) {
+                return action;
+            }
+            return self.confirmed(action);
+        }
+
+        if self.filter.handle_key_event(key) {
+            return Action::Noop;
+        }
+
+        match key.code {
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.open_scale();
+                Action::Noop
+            }
+            KeyCode::Char('r') => {
+                self.open_redeploy();
+                Action::Noop
+            }
+            KeyCode::Char('e') => match self.table_state.selected() {
+                Some(i) => Action::Navigate(Route::Events {
+                    cluster_arn: self.cluster_arn.clone(),
+                    service_arn: self.props.services[i].arn.clone(),
+                }),
+                None => Action::Noop,
+            },
+            KeyCode::Char('h') => match self.table_state.selected() {
+                Some(i) => Action::Navigate(Route::History {
+                    cluster_arn: self.cluster_arn.clone(),
+                    service_arn: self.props.services[i].arn.clone(),
+                }),
+                None => Action::Noop,
+            },
+            KeyCode::Char('k') => {
+                select_previous(&mut self.table_state, self.props.services.len());
+                Action::Noop
+            }
+            KeyCode::Char('j') => {
+                select_next(&mut self.table_state, self.props.services.len());
+                Action::Noop
+            }
+            KeyCode::Enter => match self.table_state.selected() {
+                Some(i) => Action::Navigate(Route::Tasks {
+                    cluster_arn: self.cluster_arn.clone(),
+                    service_name: Some(self.props.services[i].name.clone()),
+                }),
+                None => Action::Noop,
+            },
+            KeyCode::Char('d') => match self.table_state.selected() {
+                Some(i) => Action::Navigate(Route::Describe {
+                    target: DescribeTarget::Service {
+                        cluster_arn: self.cluster_arn.clone(),
+                        service_arn: self.props.services[i].arn.clone(),
+                    },
+                }),
+                None => Action::Noop,
+            },
+            _ => Action::Noop,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let selected_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(tailwind::CYAN.c100);
+
+        let header = [
+            "NAME",
+            "STATUS",
+            "DESIRED",
+            "RUNNING",
+            "PENDING",
+            "LAUNCH TYPE",
+            "TASK DEFINITION",
+        ]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(
+            Style::default()
+                .bold()
+                .bg(tailwind::CYAN.c200)
+                .fg(tailwind::BLACK),
+        );
+        let rows = self.props.services.iter().map(|data| {
+            let item = [
+                data.name.clone(),
+                data.status.clone(),
+                data.desired_count.to_string(),
+                data.running_count.to_string(),
+                data.pending_count.to_string(),
+                data.launch_type.clone(),
+                data.task_definition.clone(),
+            ];
+            let row = item
+                .into_iter()
+                .map(|content| Cell::from(self.filter.highlight(content)))
+                .collect::<Row>();
+            match self.props.scaling.contains(&data.arn) {
+                true => row.fg(tailwind::YELLOW.c300),
+                false => row,
+            }
+        });
+
+        let mut t = Table::new(
+            rows,
+            [
+                Constraint::Min(20),
+                Constraint::Length(10),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(12),
+                Constraint::Min(20),
+            ],
+        )
+        .header(header)
+        .highlight_style(selected_style);
+        if self.props.loading {
+            t = t.footer(loading_footer(self.props.services.len()));
+        }
+
+        frame.render_stateful_widget(
+            t.block(
+                Block::bordered()
+                    .title(format!(
+                        " Services({})[{}] {}",
+                        resource_name(&self.cluster_arn),
+                        self.props.services.len(),
+                        self.filter.title()
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .style(Style::default().bold().fg(tailwind::CYAN.c200)),
+            ),
+            rect,
+            &mut self.table_state,
+        );
+
+        if let Some(confirm) = &mut self.confirm {
+            let confirm_area = confirm.area(rect);
+            confirm.draw(frame, confirm_area);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    const CLUSTER_ARN: &str = "arn:aws:ecs:us-east-1:123456789012:cluster/demo";
+    const SERVICE_ARN: &str = "arn:aws:ecs:us-east-1:123456789012:service/demo/web";
+
+    fn services(scaling_range: Option<(i32, i32)>) -> Services {
+        let service = ServiceItem {
+            arn: SERVICE_ARN.to_string(),
+            name: "web".to_string(),
+            status: "ACTIVE".to_string(),
+            desired_count: 2,
+            running_count: 2,
+            pending_count: 0,
+            launch_type: "FARGATE".to_string(),
+            task_definition: "web:3".to_string(),
+            scaling_range,
+        };
+        let state = State {
+            services: HashMap::from([(CLUSTER_ARN.to_string(), vec![service])]),
+            ..State::default()
+        };
+
+        let mut services = Services::new(CLUSTER_ARN.to_string(), 5);
+        services.set_state(&state);
+        services.handle_key_event(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE));
+        services
+    }
+
+    fn press(services: &mut Services, code: KeyCode) -> Action {
+        services.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    // replaces the current desired count in the dialog and submits it
+    fn scale_to(services: &mut Services, input: &str) -> Action {
+        services.handle_key_event(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        assert!(services.confirm.is_some());
+        press(services, KeyCode::Backspace);
+        for c in input.chars() {
+            press(services, KeyCode::Char(c));
+        }
+        press(services, KeyCode::Enter)
+    }
+
+    fn scale_action(desired_count: i32) -> Action {
+        Action::ScaleService {
+            cluster_arn: CLUSTER_ARN.to_string(),
+            service_arn: SERVICE_ARN.to_string(),
+            desired_count,
+        }
+    }
+
+    #[test]
+    fn scales_within_auto_scaling_range() {
+        let mut services = services(Some((1, 4)));
+
+        assert_eq!(scale_to(&mut services, "4"), scale_action(4));
+        assert!(services.confirm.is_none());
+    }
+
+    #[test]
+    fn keeps_dialog_open_outside_auto_scaling_range() {
+        for input in ["5", "0"] {
+            let mut services = services(Some((1, 4)));
+
+            assert_eq!(scale_to(&mut services, input), Action::Noop);
+            assert!(services.confirm.is_some());
+
+            // escape cancels without scaling
+            assert_eq!(press(&mut services, KeyCode::Esc), Action::Noop);
+            assert!(services.confirm.is_none());
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_counts() {
+        for input in ["-1", "two", ""] {
+            let mut services = services(None);
+
+            assert_eq!(scale_to(&mut services, input), Action::Noop);
+            assert!(services.confirm.is_some());
+        }
+    }
+
+    #[test]
+    fn confirms_large_jump_again() {
+        let mut services = services(None);
+
+        // 2 -> 7 is a jump of 5, not above the limit
+        assert_eq!(scale_to(&mut services, "7"), scale_action(7));
+
+        let mut services = self::services(None);
+        assert_eq!(scale_to(&mut services, "8"), Action::Noop);
+        assert!(services.confirming_jump);
+        assert_eq!(press(&mut services, KeyCode::Enter), scale_action(8));
+        assert!(services.confirm.is_none());
+        assert!(!services.confirming_jump);
+    }
+
+    #[test]
+    fn cancels_large_jump() {
+        let mut services = services(None);
+
+        assert_eq!(scale_to(&mut services, "0"), scale_action(0));
+
+        let mut services = self::services(Some((0, 100)));
+        assert_eq!(scale_to(&mut services, "50"), Action::Noop);
+        assert_eq!(press(&mut services, KeyCode::Esc), Action::Noop);
+        assert!(services.confirm.is_none());
+        assert!(!services.confirming_jump);
+    }
+}
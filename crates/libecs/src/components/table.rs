@@ -0,0 +1,176 @@
+use std::{
+    cmp::Ordering,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use aws_sdk_ecs::primitives::DateTime;
+use ratatui::{
+    prelude::Stylize,
+    widgets::{Row, TableState},
+};
+
+// keep the selection inside the table after its rows changed, e.g. when a
+// filter hides the selected row
+pub fn clamp_selection(table_state: &mut TableState, len: usize) {
+    if let Some(i) = table_state.selected() {
+        if i >= len {
+            table_state.select(len.checked_sub(1));
+        }
+    }
+}
+
+// row below the selected one, the first when nothing is selected, the
+// selection stops at the last row
+pub fn next_index(selected: Option<usize>, len: usize) -> Option<usize> {
+    match (selected, len) {
+        (_, 0) => None,
+        (Some(i), len) => Some((i + 1).min(len - 1)),
+        (None, _) => Some(0),
+    }
+}
+
+// row above the selected one, the last when nothing is selected
+pub fn previous_index(selected: Option<usize>, len: usize) -> Option<usize> {
+    match (selected, len) {
+        (_, 0) => None,
+        (Some(i), len) => Some(i.saturating_sub(1).min(len - 1)),
+        (None, len) => Some(len - 1),
+    }
+}
+
+// `j` and `k` of the tables
+pub fn select_next(table_state: &mut TableState, len: usize) {
+    if let Some(i) = next_index(table_state.selected(), len) {
+        table_state.select(Some(i));
+    }
+}
+
+pub fn select_previous(table_state: &mut TableState, len: usize) {
+    if let Some(i) = previous_index(table_state.selected(), len) {
+        table_state.select(Some(i));
+    }
+}
+
+// footer shown while the remaining pages of a list call are fetched
+pub fn loading_footer(rows: usize) -> Row<'static> {
+    let text = match rows {
+        0 => "loading…",
+        _ => "loading more…",
+    };
+    Row::new([text]).dark_gray().italic()
+}
+
+// time elapsed since `since`, e.g. "3m12s"
+pub fn age(since: Option<&DateTime>) -> String {
+    let Some(since) = since else {
+        return String::new();
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default();
+    let secs = (now - since.secs()).max(0);
+
+    match secs {
+        s if s < 60 => format!("{s}s"),
+        s if s < 60 * 60 => format!("{}m{}s", s / 60, s % 60),
+        s if s < 24 * 60 * 60 => format!("{}h{}m", s / 3600, s % 3600 / 60),
+        s => format!("{}d{}h", s / 86400, s % 86400 / 3600),
+    }
+}
+
+// column sorting cycled with `s`, `S` flips the direction
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Sort {
+    column: usize,
+    descending: bool,
+}
+
+impl Sort {
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn next_column(&mut self, columns: usize) {
+        self.column = (self.column + 1) % columns.max(1);
+    }
+
+    pub fn reverse(&mut self) {
+        self.descending = !self.descending;
+    }
+
+    pub fn apply(&self, ordering: Ordering) -> Ordering {
+        match self.descending {
+            true => ordering.reverse(),
+            false => ordering,
+        }
+    }
+
+    pub fn header(&self, column: usize, title: &str) -> String {
+        match (column == self.column, self.descending) {
+            (true, false) => format!("{title}↑"),
+            (true, true) => format!("{title}↓"),
+            (false, _) => title.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_selection_within_rows() {
+        assert_eq!(next_index(None, 3), Some(0));
+        assert_eq!(next_index(Some(1), 3), Some(2));
+        assert_eq!(next_index(Some(2), 3), Some(2));
+        assert_eq!(next_index(Some(0), 0), None);
+
+        assert_eq!(previous_index(None, 3), Some(2));
+        assert_eq!(previous_index(Some(1), 3), Some(0));
+        assert_eq!(previous_index(Some(0), 3), Some(0));
+        // rows were filtered out since the selection was made
+        assert_eq!(previous_index(Some(7), 3), Some(2));
+        assert_eq!(previous_index(None, 0), None);
+    }
+
+    #[test]
+    fn keeps_selection_when_table_is_empty() {
+        let mut table_state = TableState::default().with_selected(Some(1));
+        select_next(&mut table_state, 0);
+        select_previous(&mut table_state, 0);
+        assert_eq!(table_state.selected(), Some(1));
+
+        clamp_selection(&mut table_state, 0);
+        assert_eq!(table_state.selected(), None);
+    }
+
+    #[test]
+    fn cycles_sort_columns() {
+        let mut sort = Sort::default();
+        assert_eq!(sort.column(), 0);
+        assert_eq!(sort.header(0, "NAME"), "NAME↑");
+        assert_eq!(sort.header(1, "REGION"), "REGION");
+
+        sort.next_column(3);
+        sort.next_column(3);
+        assert_eq!(sort.column(), 2);
+        sort.next_column(3);
+        assert_eq!(sort.column(), 0);
+    }
+
+    #[test]
+    fn reverses_sort_direction() {
+        let mut sort = Sort::default();
+        assert_eq!(sort.apply(Ordering::Less), Ordering::Less);
+
+        sort.reverse();
+        assert_eq!(sort.apply(Ordering::Less), Ordering::Greater);
+        assert_eq!(sort.apply(Ordering::Equal), Ordering::Equal);
+        assert_eq!(sort.header(0, "NAME"), "NAME↓");
+
+        // the direction is kept for the next column
+        sort.next_column(3);
+        assert_eq!(sort.header(1, "REGION"), "REGION↓");
+    }
+}
@@ -0,0 +1,176 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use ratatui::{
+    layout::{Alignment, Constraint, Rect},
+    prelude::Stylize,
+    style::{palette::tailwind, Modifier, Style},
+    widgets::{Block, Cell, Row, Table, TableState},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    route::{DescribeTarget, Route},
+    state_store::{action::Action, Listing, State, TaskDefinitionItem},
+};
+
+use super::{
+    filter::Filter,
+    table::{clamp_selection, loading_footer, select_next, select_previous},
+    Component,
+};
+
+#[derive(Default)]
+struct Props {
+    task_definitions: Vec<TaskDefinitionItem>,
+    loading: bool,
+}
+
+impl Props {
+    fn from_state(state: &State, cluster_arn: &Option<String>, filter: &Filter) -> Self {
+        let task_definitions = state
+            .task_definitions
+            .get(cluster_arn)
+            .into_iter()
+            .flatten()
+            .filter(|task_definition| {
+                filter.matches(&[&task_definition.family, &task_definition.revision])
+            })
+            .cloned()
+            .collect();
+
+        Self {
+            task_definitions,
+            loading: state.loading.contains(&Listing::TaskDefinitions {
+                cluster_arn: cluster_arn.clone(),
+            }),
+        }
+    }
+}
+
+pub struct TaskDefinitions {
+    cluster_arn: Option<String>,
+    action_tx: Option<UnboundedSender<Action>>,
+    props: Props,
+    filter: Filter,
+    table_state: TableState,
+}
+
+impl TaskDefinitions {
+    pub fn new(cluster_arn: Option<String>) -> Self {
+        Self {
+            cluster_arn,
+            action_tx: None,
+            props: Props::default(),
+            filter: Filter::default(),
+            table_state: TableState::default().with_selected(None),
+        }
+    }
+}
+
+impl Component for TaskDefinitions {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn init(&mut self) -> Result<()> {
+        if let Some(tx) = self.action_tx.clone() {
+            tx.send(Action::GetTaskDefinitions {
+                cluster_arn: self.cluster_arn.clone(),
+            })?;
+        }
+        Ok(())
+    }
+
+    fn captures_key(&self, key: &KeyEvent) -> bool {
+        self.filter.captures_key(key)
+    }
+
+    fn set_state(&mut self, state: &State) {
+        self.props = Props::from_state(state, &self.cluster_arn, &self.filter);
+        clamp_selection(&mut self.table_state, self.props.task_definitions.len());
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::Noop;
+        }
+
+        if self.filter.handle_key_event(key) {
+            return Action::Noop;
+        }
+
+        match key.code {
+            KeyCode::Char('k') => {
+                select_previous(&mut self.table_state, self.props.task_definitions.len());
+                Action::Noop
+            }
+            KeyCode::Char('j') => {
+                select_next(&mut self.table_state, self.props.task_definitions.len());
+                Action::Noop
+            }
+            KeyCode::Char('d') => match self.table_state.selected() {
+                Some(i) => Action::Navigate(Route::Describe {
+                    target: DescribeTarget::TaskDefinition {
+                        task_definition_arn: self.props.task_definitions[i].arn.clone(),
+                    },
+                }),
+                None => Action::Noop,
+            },
+            _ => Action::Noop,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let selected_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(tailwind::CYAN.c100);
+
+        let header = ["FAMILY", "REVISION", "ARN"]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .style(
+                Style::default()
+                    .bold()
+                    .bg(tailwind::CYAN.c200)
+                    .fg(tailwind::BLACK),
+            );
+        let rows = self.props.task_definitions.iter().map(|data| {
+            let item = [&data.family, &data.revision, &data.arn];
+            item.into_iter()
+                .map(|content| Cell::from(self.filter.highlight(content.clone())))
+                .collect::<Row>()
+        });
+
+        let mut t = Table::new(
+            rows,
+            [
+                Constraint::Min(20),
+                Constraint::Length(10),
+                Constraint::Min(40),
+            ],
+        )
+        .header(header)
+        .highlight_style(selected_style);
+        if self.props.loading {
+            t = t.footer(loading_footer(self.props.task_definitions.len()));
+        }
+
+        frame.render_stateful_widget(
+            t.block(
+                Block::bordered()
+                    .title(format!(
+                        " TaskDefinitions[{}] {}",
+                        self.props.task_definitions.len(),
+                        self.filter.title()
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .style(Style::default().bold().fg(tailwind::CYAN.c200)),
+            ),
+            rect,
+            &mut self.table_state,
+        )
+    }
+}
@@ -0,0 +1,286 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Rect},
+    prelude::Stylize,
+    style::{palette::tailwind, Modifier, Style},
+    widgets::{Block, Cell, Row, Table, TableState},
+    Frame,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    route::{resource_name, DescribeTarget, Route},
+    state_store::{action::Action, Listing, State, TaskItem, TaskKey},
+};
+
+use super::{
+    confirm::Confirm,
+    filter::Filter,
+    table::{age, clamp_selection, loading_footer, select_next, select_previous},
+    Component,
+};
+
+#[derive(Default)]
+struct Props {
+    tasks: Vec<TaskItem>,
+    loading: bool,
+}
+
+impl Props {
+    fn from_state(state: &State, key: &TaskKey, filter: &Filter) -> Self {
+        let tasks = state
+            .tasks
+            .get(key)
+            .into_iter()
+            .flatten()
+            .filter(|task| {
+                filter.matches(&[
+                    &task.id,
+                    &task.last_status,
+                    &task.health_status,
+                    &task.availability_zone,
+                    &task.task_definition,
+                ])
+            })
+            .cloned()
+            .collect();
+
+        Self {
+            tasks,
+            loading: state.loading.contains(&Listing::Tasks(key.clone())),
+        }
+    }
+}
+
+pub struct Tasks {
+    action_tx: Option<UnboundedSender<Action>>,
+    key: TaskKey,
+    props: Props,
+    filter: Filter,
+    table_state: TableState,
+    confirm: Option<Confirm>,
+}
+
+impl Tasks {
+    pub fn new(cluster_arn: String, service_name: Option<String>) -> Self {
+        Self {
+            action_tx: None,
+            key: TaskKey {
+                cluster_arn,
+                service_name,
+            },
+            props: Props::default(),
+            filter: Filter::default(),
+            table_state: TableState::default().with_selected(None),
+            confirm: None,
+        }
+    }
+
+    // `ctrl-k` asks before stopping the selected task, the reason typed into
+    // the dialog is optional
+    fn confirm_stop(&mut self) {
+        let Some(task) = self
+            .table_state
+            .selected()
+            .and_then(|i| self.props.tasks.get(i))
+        else {
+            return;
+        };
+
+        let service = task
+            .group
+            .strip_prefix("service:")
+            .unwrap_or("-")
+            .to_string();
+        let cluster_arn = self.key.cluster_arn.clone();
+        let task_arn = task.arn.clone();
+        self.confirm = Some(
+            Confirm::new(
+                "Stop task",
+                vec![
+                    ("task".to_string(), task.id.clone()),
+                    ("service".to_string(), service),
+                    ("status".to_string(), task.last_status.clone()),
+                ],
+                move |reason| {
+                    Ok(Action::StopTask {
+                        cluster_arn: cluster_arn.clone(),
+                        task_arn: task_arn.clone(),
+                        reason: (!reason.is_empty()).then(|| reason.to_string()),
+                    })
+                },
+            )
+            .with_input("reason (optional)", ""),
+        );
+    }
+
+    fn title(&self) -> String {
+        let cluster_name = resource_name(&self.key.cluster_arn);
+
+        match &self.key.service_name {
+            Some(service_name) => format!("{cluster_name}/{service_name}"),
+            None => cluster_name.to_string(),
+        }
+    }
+}
+
+impl Component for Tasks {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn init(&mut self) -> Result<()> {
+        if let Some(tx) = self.action_tx.clone() {
+            tx.send(Action::GetTasks {
+                cluster_arn: self.key.cluster_arn.clone(),
+                service_name: self.key.service_name.clone(),
+            })?;
+        }
+        Ok(())
+    }
+
+    fn captures_key(&self, key: &KeyEvent) -> bool {
+        self.confirm.is_some() || self.filter.captures_key(key)
+    }
+
+    fn set_state(&mut self, state: &State) {
+        self.props = Props::from_state(state, &self.key, &self.filter);
+        clamp_selection(&mut self.table_state, self.props.tasks.len());
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::Noop;
+        }
+
+        if let Some(confirm) = &mut self.confirm {
+            let action = confirm.handle_key_event(key);
+            if confirm.is_closed() {
+                self.confirm = None;
+            }
+            return action;
+        }
+
+        if self.filter.handle_key_event(key) {
+            return Action::Noop;
+        }
+
+        match key.code {
+            KeyCode::Char('k') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.confirm_stop();
+                Action::Noop
+            }
+            KeyCode::Char('k') => {
+                select_previous(&mut self.table_state, self.props.tasks.len());
+                Action::Noop
+            }
+            KeyCode::Char('j') => {
+                select_next(&mut self.table_state, self.props.tasks.len());
+                Action::Noop
+            }
+            KeyCode::Enter => match self.table_state.selected() {
+                Some(i) => Action::Navigate(Route::Containers {
+                    cluster_arn: self.key.cluster_arn.clone(),
+                    task_arn: self.props.tasks[i].arn.clone(),
+                }),
+                None => Action::Noop,
+            },
+            KeyCode::Char('d') => match self.table_state.selected() {
+                Some(i) => Action::Navigate(Route::Describe {
+                    target: DescribeTarget::Task {
+                        cluster_arn: self.key.cluster_arn.clone(),
+                        task_arn: self.props.tasks[i].arn.clone(),
+                    },
+                }),
+                None => Action::Noop,
+            },
+            _ => Action::Noop,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let selected_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(tailwind::CYAN.c100);
+
+        let header = [
+            "ID",
+            "LAST STATUS",
+            "DESIRED STATUS",
+            "HEALTH",
+            "AGE",
+            "AZ",
+            "CPU",
+            "MEMORY",
+            "TASK DEFINITION",
+        ]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row>()
+        .style(
+            Style::default()
+                .bold()
+                .bg(tailwind::CYAN.c200)
+                .fg(tailwind::BLACK),
+        );
+        let rows = self.props.tasks.iter().map(|data| {
+            let item = [
+                data.id.clone(),
+                data.last_status.clone(),
+                data.desired_status.clone(),
+                data.health_status.clone(),
+                age(data.started_at.as_ref()),
+                data.availability_zone.clone(),
+                data.cpu.clone(),
+                data.memory.clone(),
+                data.task_definition.clone(),
+            ];
+            item.into_iter()
+                .map(|content| Cell::from(self.filter.highlight(content)))
+                .collect::<Row>()
+        });
+
+        let mut t = Table::new(
+            rows,
+            [
+                Constraint::Length(32),
+                Constraint::Length(12),
+                Constraint::Length(15),
+                Constraint::Length(10),
+                Constraint::Length(8),
+                Constraint::Length(16),
+                Constraint::Length(6),
+                Constraint::Length(7),
+                Constraint::Min(20),
+            ],
+        )
+        .header(header)
+        .highlight_style(selected_style);
+        if self.props.loading {
+            t = t.footer(loading_footer(self.props.tasks.len()));
+        }
+
+        frame.render_stateful_widget(
+            t.block(
+                Block::bordered()
+                    .title(format!(
+                        " Tasks({})[{}] {}",
+                        self.title(),
+                        self.props.tasks.len(),
+                        self.filter.title()
+                    ))
+                    .title_alignment(Alignment::Center)
+                    .style(Style::default().bold().fg(tailwind::CYAN.c200)),
+            ),
+            rect,
+            &mut self.table_state,
+        );
+
+        if let Some(confirm) = &mut self.confirm {
+            let confirm_area = confirm.area(rect);
+            confirm.draw(frame, confirm_area);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use ratatui::{
+    layout::{Alignment, Rect},
+    prelude::Stylize,
+    style::{palette::tailwind, Style},
+    text::Line,
+    widgets::{
+        block::{Position, Title},
+        Block, Clear, Paragraph, Wrap,
+    },
+    Frame,
+};
+
+use crate::{error::AwsError, state_store::State};
+
+use super::Component;
+
+// last failed AWS call shown above the content until it is dismissed with
+// `esc`, earlier errors are shown one after the other, then the outcome of
+// the last change
+#[derive(Default)]
+pub struct Toast {
+    error: Option<AwsError>,
+    count: usize,
+    notice: Option<String>,
+}
+
+impl Toast {
+    pub fn is_visible(&self) -> bool {
+        self.error.is_some() || self.notice.is_some()
+    }
+
+    // bottom right corner of the content area
+    pub fn area(&self, rect: Rect) -> Rect {
+        let width = (rect.width * 3 / 5).max(40).min(rect.width);
+        let height = 6.min(rect.height);
+        Rect::new(
+            rect.x + rect.width - width,
+            rect.y + rect.height - height,
+            width,
+            height,
+        )
+    }
+}
+
+impl Component for Toast {
+    fn set_state(&mut self, state: &State) {
+        self.error = state.errors.last().cloned();
+        self.count = state.errors.len();
+        self.notice = state.notice.clone();
+    }
+
+    fn draw(&mut self, frame: &mut Frame, rect: Rect) {
+        let Some(error) = &self.error else {
+            if let Some(notice) = &self.notice {
+                draw_notice(frame, rect, notice);
+            }
+            return;
+        };
+
+        let dismiss = match self.count {
+            0 | 1 => " <esc> Dismiss ".to_string(),
+            count => format!(" <esc> Dismiss (+{} more) ", count - 1),
+        };
+        let lines = vec![
+            Line::from(error.message.clone()).fg(tailwind::RED.c300),
+            Line::from(format!("hint: {}", error.kind.hint())).dark_gray(),
+        ];
+
+        frame.render_widget(Clear, rect);
+        frame.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+                Block::bordered()
+                    .title(format!(" {} ({}) ", error.kind.title(), error.operation))
+                    .title(
+                        Title::from(dismiss)
+                            .position(Position::Bottom)
+                            .alignment(Alignment::Right),
+                    )
+                    .style(Style::default().bold().fg(tailwind::RED.c400)),
+            ),
+            rect,
+        );
+    }
+}
+
+fn draw_notice(frame: &mut Frame, rect: Rect, notice: &str) {
+    frame.render_widget(Clear, rect);
+    frame.render_widget(
+        Paragraph::new(Line::from(notice.to_string()).fg(tailwind::GREEN.c300))
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::bordered()
+                    .title(" Done ")
+                    .title(
+                        Title::from(" <esc> Dismiss ")
+                            .position(Position::Bottom)
+                            .alignment(Alignment::Right),
+                    )
+                    .style(Style::default().bold().fg(tailwind::GREEN.c400)),
+            ),
+        rect,
+    );
+}
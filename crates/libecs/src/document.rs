@@ -0,0 +1,231 @@
+use std::{
+    error::Error,
+    fmt,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use aws_sdk_ecs::config::{
+    interceptors::AfterDeserializationInterceptorContextRef, ConfigBag, Intercept,
+    RuntimeComponents,
+};
+use serde::{
+    de::{MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+// generic tree of an AWS JSON response, the fields of an object keep the
+// order the API returned them in
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn from_json(json: &[u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(json)
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Value, E> {
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Value, E> {
+        Ok(Value::Number(value.to_string()))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Value, E> {
+        Ok(Value::Number(value.to_string()))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Value, E> {
+        Ok(Value::Number(value.to_string()))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::String(value.to_string()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Value, E> {
+        Ok(Value::String(value))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut items = vec![];
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut fields = vec![];
+        while let Some(field) = map.next_entry()? {
+            fields.push(field);
+        }
+        Ok(Value::Object(fields))
+    }
+}
+
+// keeps the body of the response an operation was sent with, the describe
+// views show it as the API returned it rather than the SDK types, which
+// only implement `Debug` and redact sensitive fields
+#[derive(Debug, Clone, Default)]
+pub struct ResponseBody(Arc<Mutex<Option<Vec<u8>>>>);
+
+impl ResponseBody {
+    pub fn value(&self) -> Result<Value, String> {
+        let body = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        match body.as_deref() {
+            Some(body) => Value::from_json(body).map_err(|error| error.to_string()),
+            None => Err("the response has no body".to_string()),
+        }
+    }
+}
+
+impl Intercept for ResponseBody {
+    fn name(&self) -> &'static str {
+        "ResponseBody"
+    }
+
+    // the body of a response that is not streamed is read into memory
+    // before it is deserialized
+    fn read_after_deserialization(
+        &self,
+        context: &AfterDeserializationInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        _cfg: &mut ConfigBag,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let body = context.response().body().bytes().map(<[u8]>::to_vec);
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = body;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(s: &str) -> Value {
+        Value::String(s.to_string())
+    }
+
+    fn number(n: &str) -> Value {
+        Value::Number(n.to_string())
+    }
+
+    #[test]
+    fn keeps_fields_in_response_order() {
+        let value = Value::from_json(
+            br#"{"tasks": [{"taskArn": "arn:aws:ecs:us-east-1:123456789012:task/demo/task00",
+                "launchType": "FARGATE", "cpu": "256", "stoppedAt": null,
+                "createdAt": 1.7200512E9, "containers": [{"name": "web", "exitCode": 0}],
+                "attachments": []}], "failures": []}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value,
+            Value::Object(vec![
+                (
+                    "tasks".to_string(),
+                    Value::Array(vec![Value::Object(vec![
+                        (
+                            "taskArn".to_string(),
+                            string("arn:aws:ecs:us-east-1:123456789012:task/demo/task00")
+                        ),
+                        ("launchType".to_string(), string("FARGATE")),
+                        ("cpu".to_string(), string("256")),
+                        ("stoppedAt".to_string(), Value::Null),
+                        ("createdAt".to_string(), number("1720051200")),
+                        (
+                            "containers".to_string(),
+                            Value::Array(vec![Value::Object(vec![
+                                ("name".to_string(), string("web")),
+                                ("exitCode".to_string(), number("0")),
+                            ])])
+                        ),
+                        ("attachments".to_string(), Value::Array(vec![])),
+                    ])])
+                ),
+                ("failures".to_string(), Value::Array(vec![])),
+            ])
+        );
+    }
+
+    #[test]
+    fn reads_maps_and_punctuation_in_strings() {
+        let value = Value::from_json(
+            br#"{"dockerLabels": {"b": "x, y", "a": "key: value"},
+                "command": ["sh", "-c", "echo \"a, b\" {c}: [d]"]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value,
+            Value::Object(vec![
+                (
+                    "dockerLabels".to_string(),
+                    Value::Object(vec![
+                        ("b".to_string(), string("x, y")),
+                        ("a".to_string(), string("key: value")),
+                    ])
+                ),
+                (
+                    "command".to_string(),
+                    Value::Array(vec![
+                        string("sh"),
+                        string("-c"),
+                        string(r#"echo "a, b" {c}: [d]"#),
+                    ])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn reports_missing_and_malformed_bodies() {
+        let body = ResponseBody::default();
+        assert_eq!(body.value(), Err("the response has no body".to_string()));
+
+        *body.0.lock().unwrap() = Some(b"{\"clusters\": [".to_vec());
+        assert!(body.value().is_err());
+
+        *body.0.lock().unwrap() = Some(b"{\"clusters\": []}".to_vec());
+        assert_eq!(
+            body.value(),
+            Ok(Value::Object(vec![(
+                "clusters".to_string(),
+                Value::Array(vec![])
+            )]))
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use std::{error::Error, fmt};
+
+use aws_sdk_ecs::error::{ProvideErrorMetadata, SdkError};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorKind {
+    ExpiredCredentials,
+    AccessDenied,
+    Throttling,
+    Network,
+    MfaRequired,
+    Other,
+}
+
+impl ErrorKind {
+    pub fn title(&self) -> &'static str {
+        match self {
+            ErrorKind::ExpiredCredentials => "Expired credentials",
+            ErrorKind::AccessDenied => "Access denied",
+            ErrorKind::Throttling => "Throttled",
+            ErrorKind::Network => "Network error",
+            ErrorKind::MfaRequired => "MFA required",
+            ErrorKind::Other => "Error",
+        }
+    }
+
+    pub fn hint(&self) -> &'static str {
+        match self {
+            ErrorKind::ExpiredCredentials => {
+                "refresh the credentials of the profile and retry, SSO and MFA profiles are signed in from the prompt"
+            }
+            ErrorKind::AccessDenied => {
+                "the IAM principal is missing a permission for this call, check its policies"
+            }
+            ErrorKind::Throttling => "the API rate limit was exceeded, wait a moment and retry",
+            ErrorKind::Network => {
+                "the endpoint could not be reached, check the connection and region"
+            }
+            ErrorKind::MfaRequired => {
+                "enter a token code of the MFA device in the prompt to assume the role"
+            }
+            ErrorKind::Other => "see the message for details",
+        }
+    }
+
+    fn from_code(code: &str) -> Self {
+        match code {
+            "ExpiredToken"
+            | "ExpiredTokenException"
+            | "RequestExpired"
+            | "InvalidClientTokenId"
+            | "UnrecognizedClientException"
+            | "InvalidSignatureException" => ErrorKind::ExpiredCredentials,
+            "AccessDenied" | "AccessDeniedException" | "UnauthorizedOperation" => {
+                ErrorKind::AccessDenied
+            }
+            "Throttling"
+            | "ThrottlingException"
+            | "ThrottledException"
+            | "TooManyRequestsException"
+            | "RequestLimitExceeded" => ErrorKind::Throttling,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+// failed AWS call, classified so the UI can suggest what to do about it
+#[derive(Debug, Clone, PartialEq)]
+pub struct AwsError {
+    pub operation: String,
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl AwsError {
+    pub fn new<E, R>(operation: &str, error: SdkError<E, R>) -> Self
+    where
+        E: ProvideErrorMetadata + Error + 'static,
+        R: fmt::Debug,
+    {
+        let context = source_chain(&error);
+        let kind = match &error {
+            SdkError::ServiceError(_) => error
+                .code()
+                .map(ErrorKind::from_code)
+                .unwrap_or(ErrorKind::Other),
+            SdkError::TimeoutError(_) => ErrorKind::Network,
+            // credentials are resolved while the request is dispatched, a
+            // missing or expired profile shows up as a dispatch failure
+            _ if context.contains("credential") => ErrorKind::ExpiredCredentials,
+            SdkError::DispatchFailure(failure) if failure.is_io() || failure.is_timeout() => {
+                ErrorKind::Network
+            }
+            _ => ErrorKind::Other,
+        };
+
+        Self {
+            operation: operation.to_string(),
+            kind,
+            message: error.message().map(str::to_string).unwrap_or(context),
+        }
+    }
+}
+
+// "dispatch failure: io error: connection refused", unlike
+// `DisplayErrorContext` without the debug output of the whole error
+fn source_chain(error: &dyn Error) -> String {
+    let mut chain = error.to_string();
+    let mut source = error.source();
+    while let Some(error) = source {
+        chain.push_str(": ");
+        chain.push_str(&error.to_string());
+        source = error.source();
+    }
+    chain
+}
+
+impl fmt::Display for AwsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}): {}\nhint: {}",
+            self.kind.title(),
+            self.operation,
+            self.message,
+            self.kind.hint()
+        )
+    }
+}
+
+impl Error for AwsError {}
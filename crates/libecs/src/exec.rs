@@ -0,0 +1,489 @@
+use std::{env, path::PathBuf, process::Command};
+
+use aws_config::SdkConfig;
+use aws_sdk_ecs::types::{ClusterField, LaunchType, ManagedAgentName, Task};
+use aws_sdk_iam::types::PolicyEvaluationDecisionType;
+use aws_sdk_ssm::config::endpoint::{DefaultResolver, Params, ResolveEndpoint};
+use aws_types::service_config::ServiceConfigKey;
+use serde_json::json;
+use version_compare::{compare_to, Cmp};
+
+use crate::error::{AwsError, ErrorKind};
+
+pub const SESSION_MANAGER_PLUGIN: &str = "session-manager-plugin";
+// first ECS agent release that can run ECS Exec on EC2 instances
+const MIN_AGENT_VERSION: &str = "1.50.2";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Failed,
+}
+
+// one prerequisite of ECS Exec, `value` explains a warning or failure
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecCheck {
+    pub name: String,
+    pub value: String,
+    pub status: CheckStatus,
+}
+
+impl ExecCheck {
+    fn new(name: &str, value: impl Into<String>, status: CheckStatus) -> Self {
+        Self {
+            name: name.to_string(),
+            value: value.into(),
+            status,
+        }
+    }
+}
+
+// session of an ExecuteCommand call, handed to the session manager plugin
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecSession {
+    pub session_id: String,
+    pub stream_url: String,
+    pub token_value: String,
+    pub region: String,
+    // "ecs:<cluster>_<task id>_<container runtime id>"
+    pub target: String,
+    // SSM endpoint the plugin resumes the session through
+    pub endpoint: String,
+    pub container_name: String,
+}
+
+impl ExecSession {
+    // the arguments the AWS CLI passes for `aws ecs execute-command`, the
+    // profile and target are only used when the plugin resumes the session
+    pub fn plugin_command(&self, plugin: PathBuf, profile: Option<&str>) -> Command {
+        let session = json!({
+            "sessionId": self.session_id,
+            "streamUrl": self.stream_url,
+            "tokenValue": self.token_value,
+        });
+        let parameters = json!({ "Target": self.target });
+
+        let mut command = Command::new(plugin);
+        command
+            .arg(session.to_string())
+            .arg(&self.region)
+            .arg("StartSession")
+            .arg(profile.unwrap_or_default())
+            .arg(parameters.to_string())
+            .arg(&self.endpoint);
+        command
+    }
+}
+
+// `--endpoint-url` points the ECS calls at a mock or proxy, the session
+// only goes elsewhere than the public SSM endpoint when SSM itself is
+// overridden, with `AWS_ENDPOINT_URL_SSM` or the `ssm` settings of a
+// `services` section, otherwise the SDK resolves it for the partition
+pub async fn ssm_endpoint(config: &SdkConfig, region: &str) -> Result<String, AwsError> {
+    let key = ServiceConfigKey::builder()
+        .service_id("SSM")
+        .profile("endpoint_url")
+        .env("AWS_ENDPOINT_URL_SSM")
+        .build()
+        .ok();
+    let endpoint = config
+        .service_config()
+        .zip(key)
+        .and_then(|(service_config, key)| service_config.load_config(key));
+
+    let failed = |message: String| AwsError {
+        operation: "ResolveEndpoint".to_string(),
+        kind: ErrorKind::Other,
+        message,
+    };
+    let params = Params::builder()
+        .region(region)
+        .set_use_fips(config.use_fips())
+        .set_use_dual_stack(config.use_dual_stack())
+        .set_endpoint(endpoint)
+        .build()
+        .map_err(|error| failed(error.to_string()))?;
+    let resolved = DefaultResolver::new()
+        .resolve_endpoint(&params)
+        .await
+        .map_err(|error| failed(error.to_string()))?;
+    Ok(resolved.url().to_string())
+}
+
+// the session manager plugin found on the PATH
+pub fn session_manager_plugin() -> Option<PathBuf> {
+    let name = match cfg!(windows) {
+        true => format!("{SESSION_MANAGER_PLUGIN}.exe"),
+        false => SESSION_MANAGER_PLUGIN.to_string(),
+    };
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|directory| directory.join(&name))
+        .find(|path| path.is_file())
+}
+
+// the checks of the amazon-ecs-exec-checker script, for every container of
+// the task or only `container`, the first failed lookup ends the list
+pub async fn exec_checks(
+    config: &SdkConfig,
+    ecs_client: &aws_sdk_ecs::Client,
+    cluster: &str,
+    task: &str,
+    container: Option<&str>,
+) -> Result<Vec<ExecCheck>, AwsError> {
+    let mut checks = vec![match session_manager_plugin() {
+        Some(path) => ExecCheck::new(
+            "Session Manager",
+            path.display().to_string(),
+            CheckStatus::Ok,
+        ),
+        // the built-in client drives the session without the plugin
+        None if cfg!(unix) => ExecCheck::new(
+            "Session Manager",
+            format!("{SESSION_MANAGER_PLUGIN} is not on the PATH, the built-in client is used"),
+            CheckStatus::Ok,
+        ),
+        None => ExecCheck::new(
+            "Session Manager",
+            format!("{SESSION_MANAGER_PLUGIN} is not on the PATH, install it to open a shell"),
+            CheckStatus::Failed,
+        ),
+    }];
+
+    let sts_client = aws_sdk_sts::Client::new(config);
+    let caller_identity = sts_client
+        .get_caller_identity()
+        .send()
+        .await
+        .map_err(|error| AwsError::new("GetCallerIdentity", error))?;
+    let caller_arn = caller_identity.arn().unwrap_or_default();
+
+    let clusters = ecs_client
+        .describe_clusters()
+        .clusters(cluster)
+        .include(ClusterField::Configurations)
+        .send()
+        .await
+        .map_err(|error| AwsError::new("DescribeClusters", error))?;
+    let Some(described) = clusters.clusters().first() else {
+        let reason = clusters
+            .failures()
+            .first()
+            .and_then(|failure| failure.reason())
+            .unwrap_or("MISSING");
+        checks.push(ExecCheck::new(
+            "Cluster",
+            format!("{cluster} does not exist ({reason})"),
+            CheckStatus::Failed,
+        ));
+        return Ok(checks);
+    };
+    checks.push(
+        match described
+            .configuration()
+            .and_then(|configuration| configuration.execute_command_configuration())
+        {
+            Some(configuration) => ExecCheck::new(
+                "Audit Logging",
+                configuration
+                    .logging()
+                    .map(|logging| logging.to_string())
+                    .unwrap_or("DEFAULT".to_string()),
+                CheckStatus::Ok,
+            ),
+            None => ExecCheck::new("Audit Logging", "Not Configured", CheckStatus::Warning),
+        },
+    );
+
+    let tasks = ecs_client
+        .describe_tasks()
+        .cluster(cluster)
+        .tasks(task)
+        .send()
+        .await
+        .map_err(|error| AwsError::new("DescribeTasks", error))?;
+    let Some(described) = tasks.tasks().first() else {
+        let reason = tasks
+            .failures()
+            .first()
+            .and_then(|failure| failure.reason())
+            .unwrap_or("MISSING");
+        checks.push(ExecCheck::new(
+            "Task",
+            format!("{task} does not exist ({reason})"),
+            CheckStatus::Failed,
+        ));
+        return Ok(checks);
+    };
+    let task_arn = described.task_arn().unwrap_or(task);
+
+    let iam_client = aws_sdk_iam::Client::new(config);
+    let caller_arn = principal_arn(&iam_client, caller_arn).await;
+    // the simulation leaves out the request context, so policies with
+    // conditions come out as an implicit deny for calls that succeed, the
+    // ExecuteCommand call itself reports a real denial
+    checks.push(
+        match simulate(&iam_client, &caller_arn, "ecs:ExecuteCommand", task_arn).await {
+            Ok(PolicyEvaluationDecisionType::Allowed) => {
+                ExecCheck::new("ecs:ExecuteCommand", "allowed", CheckStatus::Ok)
+            }
+            Ok(decision) => ExecCheck::new(
+                "ecs:ExecuteCommand",
+                format!("{decision} for {caller_arn}"),
+                CheckStatus::Warning,
+            ),
+            Err(error) => ExecCheck::new(
+                "ecs:ExecuteCommand",
+                format!("not simulated, {}", error.message),
+                CheckStatus::Warning,
+            ),
+        },
+    );
+    // ECS starts the session on behalf of the caller, only an explicit deny
+    // gets in the way
+    checks.push(
+        match simulate(&iam_client, &caller_arn, "ssm:StartSession", task_arn).await {
+            Ok(PolicyEvaluationDecisionType::ExplicitDeny) => ExecCheck::new(
+                "ssm:StartSession",
+                format!("explicitly denied for {caller_arn}"),
+                CheckStatus::Failed,
+            ),
+            Ok(decision) => {
+                ExecCheck::new("ssm:StartSession", decision.to_string(), CheckStatus::Ok)
+            }
+            Err(error) => ExecCheck::new(
+                "ssm:StartSession",
+                format!("not simulated, {}", error.message),
+                CheckStatus::Warning,
+            ),
+        },
+    );
+
+    checks.push(task_status(described));
+    checks.extend(platform(ecs_client, cluster, described).await?);
+    checks.push(match described.enable_execute_command() {
+        true => ExecCheck::new("Exec Command Enabled", "OK", CheckStatus::Ok),
+        false => ExecCheck::new(
+            "Exec Command Enabled",
+            "NO, update the service with --enable-execute-command and start new tasks",
+            CheckStatus::Failed,
+        ),
+    });
+
+    for described in described
+        .containers()
+        .iter()
+        .filter(|described| container.is_none() || described.name() == container)
+    {
+        let name = format!("Exec Agent ({})", described.name().unwrap_or_default());
+        let status = described
+            .managed_agents()
+            .iter()
+            .find(|agent| agent.name() == Some(&ManagedAgentName::ExecuteCommandAgent))
+            .and_then(|agent| agent.last_status());
+        checks.push(match status {
+            Some("RUNNING") => ExecCheck::new(&name, "RUNNING", CheckStatus::Ok),
+            Some(status) => ExecCheck::new(&name, status, CheckStatus::Failed),
+            None => ExecCheck::new(&name, "not started", CheckStatus::Failed),
+        });
+    }
+
+    Ok(checks)
+}
+
+// sessions of an assumed role are simulated as the role itself, the
+// session ARN leaves out the path of the role, e.g. the
+// /aws-reserved/sso.amazonaws.com/ of SSO users, so GetRole looks it up
+async fn principal_arn(iam_client: &aws_sdk_iam::Client, caller_arn: &str) -> String {
+    let Some((prefix, resource)) = caller_arn.split_once(":assumed-role/") else {
+        return caller_arn.to_string();
+    };
+    let role = resource.split('/').next().unwrap_or_default();
+    let role_arn = iam_client
+        .get_role()
+        .role_name(role)
+        .send()
+        .await
+        .ok()
+        .and_then(|output| output.role().map(|role| role.arn().to_string()));
+    // without iam:GetRole only roles without a path can be simulated
+    role_arn.unwrap_or_else(|| format!("{}:role/{role}", prefix.replacen(":sts:", ":iam:", 1)))
+}
+
+async fn simulate(
+    iam_client: &aws_sdk_iam::Client,
+    caller_arn: &str,
+    action: &str,
+    resource_arn: &str,
+) -> Result<PolicyEvaluationDecisionType, AwsError> {
+    let simulated = iam_client
+        .simulate_principal_policy()
+        .policy_source_arn(caller_arn)
+        .action_names(action)
+        .resource_arns(resource_arn)
+        .send()
+        .await
+        .map_err(|error| AwsError::new("SimulatePrincipalPolicy", error))?;
+
+    Ok(simulated
+        .evaluation_results()
+        .first()
+        .map(|result| result.eval_decision().clone())
+        .unwrap_or(PolicyEvaluationDecisionType::from("UNKNOWN")))
+}
+
+fn task_status(task: &Task) -> ExecCheck {
+    let status = task.last_status().unwrap_or_default();
+    match status {
+        "RUNNING" => ExecCheck::new("Task Status", status, CheckStatus::Ok),
+        "PROVISIONING" | "ACTIVATING" | "PENDING" => {
+            ExecCheck::new("Task Status", status, CheckStatus::Warning)
+        }
+        "STOPPED" => ExecCheck::new(
+            "Task Status",
+            format!("{status} ({})", task.stopped_reason().unwrap_or_default()),
+            CheckStatus::Failed,
+        ),
+        _ => ExecCheck::new("Task Status", status, CheckStatus::Failed),
+    }
+}
+
+// Fargate platform version, or ECS agent version of the EC2 instance
+async fn platform(
+    ecs_client: &aws_sdk_ecs::Client,
+    cluster: &str,
+    task: &Task,
+) -> Result<Vec<ExecCheck>, AwsError> {
+    let launch_type = task
+        .launch_type()
+        .cloned()
+        .unwrap_or_else(|| LaunchType::from("UNKNOWN"));
+    let mut checks = vec![ExecCheck::new(
+        "Launch Type",
+        launch_type.to_string(),
+        match launch_type {
+            LaunchType::Fargate | LaunchType::Ec2 => CheckStatus::Ok,
+            _ => CheckStatus::Warning,
+        },
+    )];
+
+    match launch_type {
+        LaunchType::Fargate => {
+            let platform_family = task.platform_family().unwrap_or_default();
+            let required = match platform_family {
+                _ if platform_family.contains("Windows") => "1.0.0",
+                _ => "1.4.0",
+            };
+            let version = task.platform_version().unwrap_or_default();
+            checks.push(version_check("Platform Version", version, required));
+        }
+        LaunchType::Ec2 => {
+            let Some(container_instance_arn) = task.container_instance_arn() else {
+                return Ok(checks);
+            };
+            let container_instances = ecs_client
+                .describe_container_instances()
+                .cluster(cluster)
+                .container_instances(container_instance_arn)
+                .send()
+                .await
+                .map_err(|error| AwsError::new("DescribeContainerInstances", error))?;
+            let version = container_instances
+                .container_instances()
+                .first()
+                .and_then(|instance| instance.version_info())
+                .and_then(|info| info.agent_version())
+                .unwrap_or_default();
+            checks.push(version_check("Agent Version", version, MIN_AGENT_VERSION));
+        }
+        _ => {}
+    }
+
+    Ok(checks)
+}
+
+fn version_check(name: &str, version: &str, required: &str) -> ExecCheck {
+    match compare_to(version, required, Cmp::Ge).unwrap_or(false) {
+        true => ExecCheck::new(name, version, CheckStatus::Ok),
+        false => ExecCheck::new(
+            name,
+            format!("{version} (Required: >= {required})"),
+            CheckStatus::Failed,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_types::service_config::LoadServiceConfig;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct Overrides(&'static [(&'static str, &'static str)]);
+
+    impl LoadServiceConfig for Overrides {
+        fn load_config(&self, key: ServiceConfigKey<'_>) -> Option<String> {
+            self.0
+                .iter()
+                .find(|(env, _)| *env == key.env())
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn keeps_public_ssm_endpoint_for_ecs_override() {
+        let config = SdkConfig::builder()
+            .endpoint_url("http://127.0.0.1:4566")
+            .service_config(Overrides(&[(
+                "AWS_ENDPOINT_URL_ECS",
+                "http://127.0.0.1:4566",
+            )]))
+            .build();
+
+        assert_eq!(
+            ssm_endpoint(&config, "eu-west-1").await.unwrap(),
+            "https://ssm.eu-west-1.amazonaws.com"
+        );
+        assert_eq!(
+            ssm_endpoint(&SdkConfig::builder().build(), "us-east-1")
+                .await
+                .unwrap(),
+            "https://ssm.us-east-1.amazonaws.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn resolves_ssm_endpoint_of_partition() {
+        let config = SdkConfig::builder().build();
+        assert_eq!(
+            ssm_endpoint(&config, "cn-north-1").await.unwrap(),
+            "https://ssm.cn-north-1.amazonaws.com.cn"
+        );
+        assert_eq!(
+            ssm_endpoint(&config, "us-gov-west-1").await.unwrap(),
+            "https://ssm.us-gov-west-1.amazonaws.com"
+        );
+
+        let config = SdkConfig::builder().use_fips(true).build();
+        assert_eq!(
+            ssm_endpoint(&config, "us-east-1").await.unwrap(),
+            "https://ssm-fips.us-east-1.amazonaws.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn uses_ssm_override() {
+        let config = SdkConfig::builder()
+            .service_config(Overrides(&[(
+                "AWS_ENDPOINT_URL_SSM",
+                "https://ssm.vpce.example.com",
+            )]))
+            .build();
+
+        assert_eq!(
+            ssm_endpoint(&config, "us-east-1").await.unwrap(),
+            "https://ssm.vpce.example.com"
+        );
+    }
+}
@@ -1,9 +1,15 @@
 mod components;
+mod document;
+mod route;
 mod state_store;
 mod termination;
+mod tui;
 mod ui;
 
 pub mod app;
+pub mod error;
+pub mod exec;
+pub mod options;
 
 pub use app::run_app;
 
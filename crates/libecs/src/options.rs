@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+// settings of the terminal UI that are not part of the AWS config
+#[derive(Debug, Clone, Default)]
+pub struct AppOptions {
+    // profile the config was loaded from, the config does not keep its name
+    pub profile: Option<String>,
+    // regions the clusters view lists clusters from
+    pub regions: Regions,
+    // roles assumed to list the clusters of other accounts next to the
+    // clusters of the config
+    pub roles: Vec<RoleOptions>,
+    // how often the visible view is polled again, none turns it off
+    pub refresh_interval: Option<Duration>,
+    // scaling a service by more tasks than this asks for a confirmation
+    pub scale_confirm_above: i32,
+    // task definition revisions listed in the history of a service
+    pub revisions: i32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Regions {
+    // the region of the config
+    #[default]
+    Current,
+    List(Vec<String>),
+    // every region enabled for the account, resolved with account:ListRegions
+    AllEnabled,
+}
+
+impl Regions {
+    // `all` or a comma separated list of regions, nothing selects the
+    // region of the config
+    pub fn parse(regions: &[String]) -> Self {
+        match regions {
+            [] => Regions::Current,
+            [all] if all == "all" => Regions::AllEnabled,
+            regions => Regions::List(regions.to_vec()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoleOptions {
+    pub role_arn: String,
+    pub external_id: Option<String>,
+    // roles that require MFA are assumed with a token code of this device
+    pub mfa_serial: Option<String>,
+}
+
+impl RoleOptions {
+    // "<role arn>[,external-id=<id>][,mfa-serial=<arn>]"
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut parts = value.split(',');
+        let role_arn = parts.next().unwrap_or_default().trim();
+        if !role_arn.starts_with("arn:") {
+            return Err(format!("'{role_arn}' is not a role ARN"));
+        }
+
+        let mut role = RoleOptions {
+            role_arn: role_arn.to_string(),
+            external_id: None,
+            mfa_serial: None,
+        };
+        for part in parts {
+            match part.trim().split_once('=') {
+                Some(("external-id", id)) => role.external_id = Some(id.to_string()),
+                Some(("mfa-serial", serial)) => role.mfa_serial = Some(serial.to_string()),
+                _ => {
+                    return Err(format!(
+                    "unknown role option '{part}', expected external-id=<id> or mfa-serial=<arn>"
+                ))
+                }
+            }
+        }
+
+        Ok(role)
+    }
+}
@@ -0,0 +1,174 @@
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DescribeTarget {
+    Cluster {
+        cluster_arn: String,
+    },
+    Service {
+        cluster_arn: String,
+        service_arn: String,
+    },
+    Task {
+        cluster_arn: String,
+        task_arn: String,
+    },
+    TaskDefinition {
+        task_definition_arn: String,
+    },
+    ContainerInstance {
+        cluster_arn: String,
+        container_instance_arn: String,
+    },
+}
+
+impl DescribeTarget {
+    pub fn name(&self) -> &str {
+        match self {
+            DescribeTarget::Cluster { cluster_arn } => resource_name(cluster_arn),
+            DescribeTarget::Service { service_arn, .. } => resource_id(service_arn),
+            DescribeTarget::Task { task_arn, .. } => resource_id(task_arn),
+            DescribeTarget::TaskDefinition {
+                task_definition_arn,
+            } => resource_id(task_definition_arn),
+            DescribeTarget::ContainerInstance {
+                container_instance_arn,
+                ..
+            } => resource_id(container_instance_arn),
+        }
+    }
+
+    // ARN that tells the region and account the target lives in
+    pub fn scope_arn(&self) -> &str {
+        match self {
+            DescribeTarget::Cluster { cluster_arn }
+            | DescribeTarget::Service { cluster_arn, .. }
+            | DescribeTarget::Task { cluster_arn, .. }
+            | DescribeTarget::ContainerInstance { cluster_arn, .. } => cluster_arn,
+            DescribeTarget::TaskDefinition {
+                task_definition_arn,
+            } => task_definition_arn,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Route {
+    Clusters,
+    Services {
+        cluster_arn: String,
+    },
+    Tasks {
+        cluster_arn: String,
+        service_name: Option<String>,
+    },
+    Containers {
+        cluster_arn: String,
+        task_arn: String,
+    },
+    Logs {
+        cluster_arn: String,
+        task_arn: String,
+        container_name: String,
+    },
+    Deployments {
+        cluster_arn: String,
+        service_arn: String,
+    },
+    History {
+        cluster_arn: String,
+        service_arn: String,
+    },
+    Events {
+        cluster_arn: String,
+        service_arn: String,
+    },
+    // task definitions of the account and region of the cluster the prompt
+    // was opened from, or of the config without one
+    TaskDefinitions {
+        cluster_arn: Option<String>,
+    },
+    ContainerInstances {
+        cluster_arn: String,
+    },
+    Describe {
+        target: DescribeTarget,
+    },
+}
+
+impl Route {
+    // segment shown in the breadcrumb, the root view has none
+    pub fn breadcrumb(&self) -> Option<String> {
+        match self {
+            Route::Clusters => None,
+            Route::Services { cluster_arn } => Some(resource_name(cluster_arn).to_string()),
+            Route::Tasks {
+                cluster_arn,
+                service_name,
+            } => match service_name {
+                Some(service_name) => Some(service_name.clone()),
+                None => Some(resource_name(cluster_arn).to_string()),
+            },
+            Route::Containers { task_arn, .. } => Some(format!("task/{}", resource_id(task_arn))),
+            Route::Logs { container_name, .. } => Some(format!("{container_name}/logs")),
+            Route::Deployments { service_arn, .. } => {
+                Some(format!("{}/deployments", resource_id(service_arn)))
+            }
+            Route::History { service_arn, .. } => {
+                Some(format!("{}/history", resource_id(service_arn)))
+            }
+            Route::Events { service_arn, .. } => {
+                Some(format!("{}/events", resource_id(service_arn)))
+            }
+            Route::TaskDefinitions { .. } => Some("task-definitions".to_string()),
+            Route::ContainerInstances { cluster_arn } => Some(format!(
+                "{} > container-instances",
+                resource_name(cluster_arn)
+            )),
+            Route::Describe { target } => Some(format!("describe/{}", target.name())),
+        }
+    }
+
+    pub fn cluster_arn(&self) -> Option<&str> {
+        match self {
+            Route::Clusters | Route::Describe { .. } => None,
+            Route::TaskDefinitions { cluster_arn } => cluster_arn.as_deref(),
+            Route::Services { cluster_arn }
+            | Route::Tasks { cluster_arn, .. }
+            | Route::Containers { cluster_arn, .. }
+            | Route::Logs { cluster_arn, .. }
+            | Route::Deployments { cluster_arn, .. }
+            | Route::History { cluster_arn, .. }
+            | Route::Events { cluster_arn, .. }
+            | Route::ContainerInstances { cluster_arn } => Some(cluster_arn),
+        }
+    }
+
+    pub fn service_name(&self) -> Option<&str> {
+        match self {
+            Route::Tasks { service_name, .. } => service_name.as_deref(),
+            Route::Deployments { service_arn, .. }
+            | Route::History { service_arn, .. }
+            | Route::Events { service_arn, .. } => Some(resource_id(service_arn)),
+            _ => None,
+        }
+    }
+}
+
+// "arn:aws:ecs:<region>:<account>:cluster/<name>" -> "<name>"
+pub fn resource_name(arn: &str) -> &str {
+    arn.split_once('/').map(|(_, name)| name).unwrap_or(arn)
+}
+
+// "arn:aws:ecs:<region>:<account>:task/<cluster>/<id>" -> "<id>"
+pub fn resource_id(arn: &str) -> &str {
+    arn.rsplit_once('/').map(|(_, id)| id).unwrap_or(arn)
+}
+
+// "arn:aws:ecs:<region>:<account>:cluster/<name>" -> "<region>"
+pub fn resource_region(arn: &str) -> Option<&str> {
+    arn.split(':').nth(3).filter(|region| !region.is_empty())
+}
+
+// "arn:aws:ecs:<region>:<account>:cluster/<name>" -> "<account>"
+pub fn resource_account(arn: &str) -> Option<&str> {
+    arn.split(':').nth(4).filter(|account| !account.is_empty())
+}
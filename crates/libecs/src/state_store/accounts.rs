@@ -0,0 +1,116 @@
+use std::time::{Duration, SystemTime};
+
+use aws_config::SdkConfig;
+use aws_sdk_sts::config::{Credentials, SharedCredentialsProvider};
+
+use crate::{
+    error::{AwsError, ErrorKind},
+    options::RoleOptions,
+    route::resource_account,
+};
+
+// account whose clusters are listed, either the one of the config or one
+// reached by assuming a role
+#[derive(Debug, Clone)]
+pub struct Account {
+    pub id: String,
+    // account alias, or the ID when the account has none
+    pub label: String,
+    pub config: SdkConfig,
+    expiration: Option<SystemTime>,
+}
+
+impl Account {
+    // assumed credentials are renewed a little before they run out
+    pub fn is_expired(&self) -> bool {
+        self.expiration
+            .is_some_and(|expiration| SystemTime::now() + Duration::from_secs(60) >= expiration)
+    }
+}
+
+pub async fn caller_account(config: &SdkConfig) -> Result<Account, AwsError> {
+    let sts_client = aws_sdk_sts::Client::new(config);
+    let caller_identity = sts_client
+        .get_caller_identity()
+        .send()
+        .await
+        .map_err(|error| AwsError::new("GetCallerIdentity", error))?;
+
+    let id = caller_identity.account().unwrap_or_default().to_string();
+    Ok(Account {
+        label: account_label(config, &id).await,
+        id,
+        config: config.clone(),
+        expiration: None,
+    })
+}
+
+pub async fn assume_role(
+    config: &SdkConfig,
+    role: &RoleOptions,
+    token_code: Option<String>,
+) -> Result<Account, AwsError> {
+    if role.mfa_serial.is_some() && token_code.is_none() {
+        return Err(AwsError {
+            operation: "AssumeRole".to_string(),
+            kind: ErrorKind::MfaRequired,
+            message: format!("{} requires an MFA token code", role.role_arn),
+        });
+    }
+
+    let sts_client = aws_sdk_sts::Client::new(config);
+    let assumed = sts_client
+        .assume_role()
+        .role_arn(&role.role_arn)
+        .role_session_name("raws")
+        .set_external_id(role.external_id.clone())
+        .set_serial_number(role.mfa_serial.clone())
+        .set_token_code(token_code)
+        .send()
+        .await
+        .map_err(|error| AwsError::new("AssumeRole", error))?;
+
+    let Some(credentials) = assumed.credentials() else {
+        return Err(AwsError {
+            operation: "AssumeRole".to_string(),
+            kind: ErrorKind::Other,
+            message: format!("no credentials returned for {}", role.role_arn),
+        });
+    };
+    let expiration = SystemTime::try_from(*credentials.expiration()).ok();
+    let config = config
+        .to_builder()
+        .credentials_provider(SharedCredentialsProvider::new(Credentials::new(
+            credentials.access_key_id(),
+            credentials.secret_access_key(),
+            Some(credentials.session_token().to_string()),
+            expiration,
+            "AssumeRole",
+        )))
+        .build();
+
+    let id = assumed
+        .assumed_role_user()
+        .and_then(|user| resource_account(user.arn()))
+        .unwrap_or_default()
+        .to_string();
+    Ok(Account {
+        label: account_label(&config, &id).await,
+        id,
+        config,
+        expiration,
+    })
+}
+
+// the alias needs iam:ListAccountAliases, fall back to the ID without it
+async fn account_label(config: &SdkConfig, id: &str) -> String {
+    let iam_client = aws_sdk_iam::Client::new(config);
+    match iam_client.list_account_aliases().send().await {
+        Ok(aliases) => aliases
+            .account_aliases()
+            .first()
+            .cloned()
+            .unwrap_or_else(|| id.to_string()),
+        Err(_) => id.to_string(),
+    }
+}
@@ -1,10 +1,114 @@
+use crate::{
+    error::AwsError,
+    route::{DescribeTarget, Route},
+};
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Action {
     Noop,
     Quit,
     Tick,
     Render,
+    Navigate(Route),
+    Switch(Route),
+    OpenContextSwitcher,
+    // poll the resources of the visible view again
+    Refresh(Route),
 
     GetContextInfo,
+    GetProfiles,
+    SwitchContext {
+        profile: String,
+        region: String,
+    },
     GetClusters,
+    GetServices {
+        cluster_arn: String,
+    },
+    GetTasks {
+        cluster_arn: String,
+        service_name: Option<String>,
+    },
+    DescribeTask {
+        cluster_arn: String,
+        task_arn: String,
+    },
+    // the latest events of the stream, then the newer ones on each call
+    GetLogEvents {
+        cluster_arn: String,
+        task_arn: String,
+        container_name: String,
+    },
+    // sent by the app once the logs screen of the container was closed
+    CloseLogs {
+        task_arn: String,
+        container_name: String,
+    },
+    // interactive shell in a container, once ECS Exec passes its checks
+    ExecuteCommand {
+        cluster_arn: String,
+        task_arn: String,
+        container_name: String,
+    },
+    // sent by the app once the session manager plugin exited
+    FinishExec {
+        container_name: String,
+        result: Result<(), AwsError>,
+    },
+    GetTaskDefinitions {
+        cluster_arn: Option<String>,
+    },
+    GetContainerInstances {
+        cluster_arn: String,
+    },
+    Describe(DescribeTarget),
+    StopTask {
+        cluster_arn: String,
+        task_arn: String,
+        reason: Option<String>,
+    },
+    ScaleService {
+        cluster_arn: String,
+        service_arn: String,
+        desired_count: i32,
+    },
+    // sent by the store itself until a scaled service converged
+    TrackScaling {
+        cluster_arn: String,
+        service_arn: String,
+    },
+    GetDeployments {
+        cluster_arn: String,
+        service_arn: String,
+    },
+    ForceDeployment {
+        cluster_arn: String,
+        service_arn: String,
+    },
+    GetServiceEvents {
+        cluster_arn: String,
+        service_arn: String,
+    },
+    GetRevisions {
+        cluster_arn: String,
+        service_arn: String,
+    },
+    // updates the service to a previous revision of its task definition
+    RollbackService {
+        cluster_arn: String,
+        service_arn: String,
+        task_definition_arn: String,
+    },
+    // sent by the store itself until a forced deployment completed or failed
+    TrackDeployment {
+        cluster_arn: String,
+        service_arn: String,
+    },
+    DismissToast,
+    SubmitMfaCode {
+        code: String,
+    },
+    CancelLogin,
+    // sent by the store itself once the SSO token was polled
+    FinishSsoLogin(Result<(), AwsError>),
 }
@@ -0,0 +1,270 @@
+use aws_sdk_cloudwatchlogs::Client;
+use aws_sdk_ecs::types::{ContainerDefinition, LogDriver};
+
+use crate::error::AwsError;
+
+use super::state::LogEventItem;
+
+// events asked for per GetLogEvents call, and calls made per poll before
+// the rest is left for the next one
+const PAGE_SIZE: i32 = 1000;
+const MAX_PAGES: usize = 10;
+
+// awslogs group and stream a container of a task writes to
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogSource {
+    pub group: String,
+    pub stream: String,
+    // `awslogs-region`, none for the region of the cluster
+    pub region: Option<String>,
+}
+
+impl LogSource {
+    // the stream is "<prefix>/<container>/<task id>" when the container sets
+    // `awslogs-stream-prefix`, which Fargate requires, without it the
+    // stream is named after the Docker container ID and cannot be derived
+    pub fn of_container(definition: &ContainerDefinition, task_id: &str) -> Result<Self, String> {
+        let name = definition.name().unwrap_or_default();
+        let Some(configuration) = definition.log_configuration() else {
+            return Err(format!("{name} has no log configuration"));
+        };
+        if configuration.log_driver() != &LogDriver::Awslogs {
+            return Err(format!(
+                "{name} logs with the {} driver, only awslogs is read",
+                configuration.log_driver()
+            ));
+        }
+
+        let option = |key: &str| {
+            configuration
+                .options()
+                .and_then(|options| options.get(key))
+                .cloned()
+        };
+        let group =
+            option("awslogs-group").ok_or_else(|| format!("{name} has no awslogs-group option"))?;
+        let prefix = option("awslogs-stream-prefix").ok_or_else(|| {
+            format!("{name} has no awslogs-stream-prefix option, its stream cannot be found")
+        })?;
+
+        Ok(Self {
+            group,
+            stream: format!("{prefix}/{name}/{task_id}"),
+            region: option("awslogs-region"),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct LogPage {
+    pub events: Vec<LogEventItem>,
+    // passed to the next call to only get newer events
+    pub next_token: Option<String>,
+}
+
+// the latest events of the stream when there is no token yet, else the
+// events written since the token
+pub async fn log_events(
+    client: &Client,
+    source: &LogSource,
+    token: Option<String>,
+) -> Result<LogPage, AwsError> {
+    let mut events = vec![];
+    let mut token = token;
+
+    for _ in 0..MAX_PAGES {
+        // a forward token is only honoured when reading from the head
+        let following = token.is_some();
+        let output = client
+            .get_log_events()
+            .log_group_name(&source.group)
+            .log_stream_name(&source.stream)
+            .set_next_token(token.clone())
+            .start_from_head(following)
+            .limit(PAGE_SIZE)
+            .send()
+            .await
+            .map_err(|error| AwsError::new("GetLogEvents", error))?;
+
+        let page = output
+            .events()
+            .iter()
+            .map(LogEventItem::from)
+            .collect::<Vec<_>>();
+        let next_token = output.next_forward_token().map(str::to_string);
+        // the same token comes back once the end of the stream is reached
+        let done = !following || page.is_empty() || next_token == token;
+        events.extend(page);
+        token = next_token.or(token);
+        if done {
+            break;
+        }
+    }
+
+    Ok(LogPage {
+        events,
+        next_token: token,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_config::BehaviorVersion;
+    use aws_sdk_cloudwatchlogs::config::{Credentials, Region};
+    use aws_sdk_ecs::types::LogConfiguration;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task::JoinHandle,
+    };
+
+    use super::*;
+
+    fn container(driver: LogDriver, options: &[(&str, &str)]) -> ContainerDefinition {
+        let options = options
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect::<HashMap<_, _>>();
+        ContainerDefinition::builder()
+            .name("app")
+            .log_configuration(
+                LogConfiguration::builder()
+                    .log_driver(driver)
+                    .set_options(Some(options))
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+    }
+
+    // answers each request with the next body and hands back the requests
+    async fn serve(bodies: Vec<&'static str>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let mut requests = vec![];
+            for body in bodies {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = vec![];
+                let mut buffer = [0; 4096];
+                // headers, then as much body as the content length says
+                loop {
+                    let read = socket.read(&mut buffer).await.unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request).to_lowercase();
+                    if let Some(end) = text.find("\r\n\r\n") {
+                        let length = text
+                            .lines()
+                            .find_map(|line| line.strip_prefix("content-length:"))
+                            .and_then(|length| length.trim().parse::<usize>().ok())
+                            .unwrap_or_default();
+                        if request.len() >= end + 4 + length {
+                            break;
+                        }
+                    }
+                    if read == 0 {
+                        break;
+                    }
+                }
+                requests.push(String::from_utf8_lossy(&request).to_string());
+
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/x-amz-json-1.1\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                socket.shutdown().await.unwrap();
+            }
+            requests
+        });
+
+        (endpoint, server)
+    }
+
+    fn client(endpoint: &str) -> Client {
+        let config = aws_sdk_cloudwatchlogs::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-east-1"))
+            .endpoint_url(endpoint)
+            .credentials_provider(Credentials::new("key", "secret", None, None, "test"))
+            .build();
+        Client::from_conf(config)
+    }
+
+    fn source() -> LogSource {
+        LogSource {
+            group: "/ecs/app".to_string(),
+            stream: "ecs/app/0123".to_string(),
+            region: None,
+        }
+    }
+
+    #[test]
+    fn resolves_awslogs_stream() {
+        let definition = container(
+            LogDriver::Awslogs,
+            &[
+                ("awslogs-group", "/ecs/app"),
+                ("awslogs-region", "eu-west-1"),
+                ("awslogs-stream-prefix", "ecs"),
+            ],
+        );
+
+        assert_eq!(
+            LogSource::of_container(&definition, "0123"),
+            Ok(LogSource {
+                group: "/ecs/app".to_string(),
+                stream: "ecs/app/0123".to_string(),
+                region: Some("eu-west-1".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_other_drivers_and_missing_prefix() {
+        let splunk = container(LogDriver::Splunk, &[]);
+        let no_prefix = container(LogDriver::Awslogs, &[("awslogs-group", "/ecs/app")]);
+
+        assert_eq!(
+            LogSource::of_container(&splunk, "0123"),
+            Err("app logs with the splunk driver, only awslogs is read".to_string())
+        );
+        assert!(LogSource::of_container(&no_prefix, "0123")
+            .unwrap_err()
+            .contains("awslogs-stream-prefix"));
+    }
+
+    #[tokio::test]
+    async fn reads_latest_events_then_follows() {
+        let (endpoint, server) = serve(vec![
+            r#"{"events":[{"timestamp":1000,"message":"started\n","ingestionTime":1001}],"nextForwardToken":"f/1","nextBackwardToken":"b/1"}"#,
+            r#"{"events":[{"timestamp":2000,"message":"ready","ingestionTime":2001}],"nextForwardToken":"f/2","nextBackwardToken":"b/2"}"#,
+            r#"{"events":[],"nextForwardToken":"f/2","nextBackwardToken":"b/2"}"#,
+        ])
+        .await;
+        let client = client(&endpoint);
+
+        let first = log_events(&client, &source(), None).await.unwrap();
+        let next = log_events(&client, &source(), first.next_token.clone())
+            .await
+            .unwrap();
+        let requests = server.await.unwrap();
+
+        assert_eq!(first.events.len(), 1);
+        assert_eq!(first.events[0].message, "started");
+        assert_eq!(first.next_token.as_deref(), Some("f/1"));
+        assert_eq!(next.events.len(), 1);
+        assert_eq!(next.events[0].timestamp, Some(2000));
+        assert_eq!(next.next_token.as_deref(), Some("f/2"));
+
+        assert!(requests[0].contains("Logs_20140328.GetLogEvents"));
+        assert!(requests[0].contains(r#""logGroupName":"/ecs/app""#));
+        assert!(requests[0].contains(r#""startFromHead":false"#));
+        assert!(requests[1].contains(r#""nextToken":"f/1""#));
+        assert!(requests[1].contains(r#""startFromHead":true"#));
+        assert!(requests[2].contains(r#""nextToken":"f/2""#));
+    }
+}
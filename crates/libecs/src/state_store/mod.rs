@@ -1,6 +1,12 @@
+mod accounts;
+mod logs;
+mod profiles;
+mod sso;
 mod state;
+#[allow(clippy::module_inception)]
 mod state_store;
 
 pub mod action;
+pub use profiles::REGIONS;
 pub use state::*;
 pub use state_store::StateStore;
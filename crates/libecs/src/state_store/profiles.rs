@@ -0,0 +1,261 @@
+use std::{collections::HashMap, env, fs, path::PathBuf};
+
+// regions ECS is available in, offered by the context switcher
+pub const REGIONS: [&str; 29] = [
+    "us-east-1",
+    "us-east-2",
+    "us-west-1",
+    "us-west-2",
+    "af-south-1",
+    "ap-east-1",
+    "ap-south-1",
+    "ap-south-2",
+    "ap-southeast-1",
+    "ap-southeast-2",
+    "ap-southeast-3",
+    "ap-southeast-4",
+    "ap-northeast-1",
+    "ap-northeast-2",
+    "ap-northeast-3",
+    "ca-central-1",
+    "ca-west-1",
+    "eu-central-1",
+    "eu-central-2",
+    "eu-west-1",
+    "eu-west-2",
+    "eu-west-3",
+    "eu-south-1",
+    "eu-south-2",
+    "eu-north-1",
+    "il-central-1",
+    "me-central-1",
+    "me-south-1",
+    "sa-east-1",
+];
+
+// profile to show when neither `--profile` nor `AWS_PROFILE` picked one
+pub fn active_profile(profile: Option<String>) -> String {
+    profile
+        .or_else(|| env::var("AWS_PROFILE").ok())
+        .unwrap_or_else(|| "default".to_string())
+}
+
+// names of the profiles in the shared config and credentials files, the
+// same files and environment overrides the SDK reads
+pub fn profile_names() -> Vec<String> {
+    parse_profile_names(
+        read_shared_file("AWS_CONFIG_FILE", "config").as_deref(),
+        read_shared_file("AWS_SHARED_CREDENTIALS_FILE", "credentials").as_deref(),
+    )
+}
+
+// settings of a profile, the config file wins over the credentials file
+// like in the SDK
+pub fn profile_settings(profile: &str) -> HashMap<String, String> {
+    parse_profile_settings(
+        read_shared_file("AWS_CONFIG_FILE", "config").as_deref(),
+        read_shared_file("AWS_SHARED_CREDENTIALS_FILE", "credentials").as_deref(),
+        profile,
+    )
+}
+
+// settings of a `[sso-session name]` section of the config file
+pub fn sso_session_settings(name: &str) -> HashMap<String, String> {
+    read_shared_file("AWS_CONFIG_FILE", "config")
+        .map(|config| parse_sso_session_settings(&config, name))
+        .unwrap_or_default()
+}
+
+fn parse_profile_names(config: Option<&str>, credentials: Option<&str>) -> Vec<String> {
+    let mut names = vec![];
+
+    if let Some(config) = config {
+        names.extend(sections(config).filter_map(|section| {
+            match section {
+                "default" => Some("default".to_string()),
+                section => section
+                    .strip_prefix("profile ")
+                    .map(|name| name.trim().to_string()),
+            }
+        }));
+    }
+    if let Some(credentials) = credentials {
+        names.extend(sections(credentials).map(str::to_string));
+    }
+
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn parse_profile_settings(
+    config: Option<&str>,
+    credentials: Option<&str>,
+    profile: &str,
+) -> HashMap<String, String> {
+    let mut settings = HashMap::new();
+
+    if let Some(credentials) = credentials {
+        settings.extend(section_settings(credentials, |section| section == profile));
+    }
+    if let Some(config) = config {
+        settings.extend(section_settings(config, |section| {
+            section.strip_prefix("profile ").map(str::trim) == Some(profile)
+                || (profile == "default" && section == "default")
+        }));
+    }
+    settings
+}
+
+fn parse_sso_session_settings(config: &str, name: &str) -> HashMap<String, String> {
+    section_settings(config, |section| {
+        section.strip_prefix("sso-session ").map(str::trim) == Some(name)
+    })
+}
+
+// home directory the shared files and the SSO token cache live in
+pub fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+fn read_shared_file(variable: &str, name: &str) -> Option<String> {
+    let path = match env::var_os(variable) {
+        Some(path) => PathBuf::from(path),
+        None => home_dir()?.join(".aws").join(name),
+    };
+    fs::read_to_string(path).ok()
+}
+
+// `[name]` headers of an ini file
+fn sections(contents: &str) -> impl Iterator<Item = &str> {
+    contents.lines().filter_map(section)
+}
+
+fn section(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix('[')
+        .and_then(|line| line.strip_suffix(']'))
+        .map(str::trim)
+}
+
+// `key = value` lines of the sections whose header matches, comments and
+// nested settings are skipped
+fn section_settings(contents: &str, matches: impl Fn(&str) -> bool) -> HashMap<String, String> {
+    let mut settings = HashMap::new();
+    let mut in_section = false;
+
+    for line in contents.lines() {
+        if let Some(section) = section(line) {
+            in_section = matches(section);
+            continue;
+        }
+        if !in_section || line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let line = line.trim();
+        if line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            settings.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    settings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = "\
+[default]
+region = us-east-1
+
+[profile dev]
+# assumed with the keys of the default profile
+role_arn = arn:aws:iam::123456789012:role/dev
+source_profile = default
+mfa_serial = arn:aws:iam::123456789012:mfa/dev
+s3 =
+  max_concurrent_requests = 20
+; region = eu-west-1
+
+[profile  sso ]
+sso_session = my-sso
+sso_account_id = 123456789012
+
+[sso-session my-sso]
+sso_start_url = https://example.awsapps.com/start
+sso_region = eu-west-1
+
+[services local]
+ecs =
+  endpoint_url = http://localhost:4566
+";
+
+    const CREDENTIALS: &str = "\
+[default]
+aws_access_key_id = AKIDDEFAULT
+region = ap-northeast-1
+
+[ci]
+aws_access_key_id = AKIDCI
+";
+
+    fn setting<'a>(settings: &'a HashMap<String, String>, key: &str) -> Option<&'a str> {
+        settings.get(key).map(String::as_str)
+    }
+
+    #[test]
+    fn lists_profiles_of_both_files() {
+        assert_eq!(
+            parse_profile_names(Some(CONFIG), Some(CREDENTIALS)),
+            ["ci", "default", "dev", "sso"]
+        );
+        assert_eq!(
+            parse_profile_names(None, Some(CREDENTIALS)),
+            ["ci", "default"]
+        );
+    }
+
+    #[test]
+    fn reads_profile_settings() {
+        let dev = parse_profile_settings(Some(CONFIG), Some(CREDENTIALS), "dev");
+        assert_eq!(setting(&dev, "source_profile"), Some("default"));
+        assert_eq!(
+            setting(&dev, "mfa_serial"),
+            Some("arn:aws:iam::123456789012:mfa/dev")
+        );
+        // comments and nested settings are not settings of the profile
+        assert_eq!(setting(&dev, "s3"), Some(""));
+        assert_eq!(setting(&dev, "max_concurrent_requests"), None);
+        assert_eq!(setting(&dev, "region"), None);
+
+        let sso = parse_profile_settings(Some(CONFIG), None, "sso");
+        assert_eq!(setting(&sso, "sso_session"), Some("my-sso"));
+    }
+
+    #[test]
+    fn prefers_config_file() {
+        let default = parse_profile_settings(Some(CONFIG), Some(CREDENTIALS), "default");
+        assert_eq!(setting(&default, "region"), Some("us-east-1"));
+        assert_eq!(setting(&default, "aws_access_key_id"), Some("AKIDDEFAULT"));
+
+        let ci = parse_profile_settings(Some(CONFIG), Some(CREDENTIALS), "ci");
+        assert_eq!(setting(&ci, "aws_access_key_id"), Some("AKIDCI"));
+    }
+
+    #[test]
+    fn reads_sso_session_section() {
+        let session = parse_sso_session_settings(CONFIG, "my-sso");
+        assert_eq!(
+            setting(&session, "sso_start_url"),
+            Some("https://example.awsapps.com/start")
+        );
+        assert_eq!(setting(&session, "sso_region"), Some("eu-west-1"));
+
+        assert!(parse_sso_session_settings(CONFIG, "other").is_empty());
+    }
+}
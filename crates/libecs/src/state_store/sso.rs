@@ -0,0 +1,323 @@
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use aws_config::{Region, SdkConfig};
+use aws_sdk_sts::primitives::{DateTime, DateTimeFormat};
+use sha1::{Digest, Sha1};
+
+use crate::error::{AwsError, ErrorKind};
+
+use super::profiles::{home_dir, profile_settings, sso_session_settings};
+
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+// IAM Identity Center settings of an SSO profile, the SDK only reads the
+// token `aws sso login` caches so an expired session is signed in again
+// with the same device authorization flow
+#[derive(Debug, Clone)]
+pub struct SsoSession {
+    start_url: String,
+    region: String,
+    // the token cache is keyed by the `sso-session` name, or by the start
+    // URL for profiles configured without one
+    cache_key: String,
+}
+
+impl SsoSession {
+    pub fn of_profile(profile: &str) -> Option<Self> {
+        Self::from_settings(&profile_settings(profile), sso_session_settings)
+    }
+
+    // `sso_session` names the `[sso-session]` section holding the settings,
+    // older profiles carry them themselves
+    fn from_settings(
+        settings: &HashMap<String, String>,
+        session_settings: impl Fn(&str) -> HashMap<String, String>,
+    ) -> Option<Self> {
+        match settings.get("sso_session") {
+            Some(name) => {
+                let session = session_settings(name);
+                Some(Self {
+                    start_url: session.get("sso_start_url")?.clone(),
+                    region: session.get("sso_region")?.clone(),
+                    cache_key: name.clone(),
+                })
+            }
+            None => {
+                let start_url = settings.get("sso_start_url")?.clone();
+                Some(Self {
+                    region: settings.get("sso_region")?.clone(),
+                    cache_key: start_url.clone(),
+                    start_url,
+                })
+            }
+        }
+    }
+}
+
+// device code the user confirms in the browser while the token is polled
+#[derive(Debug, Clone)]
+pub struct DeviceAuthorization {
+    pub verification_uri: String,
+    pub user_code: String,
+    device_code: String,
+    client_id: String,
+    client_secret: String,
+    client_expires_at: SystemTime,
+    interval: Duration,
+    expires_at: Instant,
+}
+
+pub async fn start_device_authorization(
+    config: &SdkConfig,
+    session: &SsoSession,
+) -> Result<DeviceAuthorization, AwsError> {
+    let oidc_client = oidc_client(config, session);
+    let client = oidc_client
+        .register_client()
+        .client_name("raws")
+        .client_type("public")
+        .send()
+        .await
+        .map_err(|error| AwsError::new("RegisterClient", error))?;
+    let client_id = client.client_id().unwrap_or_default().to_string();
+    let client_secret = client.client_secret().unwrap_or_default().to_string();
+
+    let authorization = oidc_client
+        .start_device_authorization()
+        .client_id(&client_id)
+        .client_secret(&client_secret)
+        .start_url(&session.start_url)
+        .send()
+        .await
+        .map_err(|error| AwsError::new("StartDeviceAuthorization", error))?;
+
+    Ok(DeviceAuthorization {
+        verification_uri: authorization
+            .verification_uri_complete()
+            .or(authorization.verification_uri())
+            .unwrap_or_default()
+            .to_string(),
+        user_code: authorization.user_code().unwrap_or_default().to_string(),
+        device_code: authorization.device_code().unwrap_or_default().to_string(),
+        client_id,
+        client_secret,
+        client_expires_at: SystemTime::UNIX_EPOCH
+            + Duration::from_secs(client.client_secret_expires_at().max(0) as u64),
+        interval: Duration::from_secs(authorization.interval().max(1) as u64),
+        expires_at: Instant::now() + Duration::from_secs(authorization.expires_in().max(0) as u64),
+    })
+}
+
+// poll until the user confirmed the code, then cache the token where the
+// SDK looks for it
+pub async fn wait_for_token(
+    config: &SdkConfig,
+    session: &SsoSession,
+    authorization: DeviceAuthorization,
+) -> Result<(), AwsError> {
+    let oidc_client = oidc_client(config, session);
+    let mut interval = authorization.interval;
+
+    loop {
+        tokio::time::sleep(interval).await;
+        if Instant::now() >= authorization.expires_at {
+            return Err(AwsError {
+                operation: "CreateToken".to_string(),
+                kind: ErrorKind::ExpiredCredentials,
+                message: "the device code expired before the sign-in was confirmed".to_string(),
+            });
+        }
+
+        let result = oidc_client
+            .create_token()
+            .client_id(&authorization.client_id)
+            .client_secret(&authorization.client_secret)
+            .grant_type(DEVICE_CODE_GRANT)
+            .device_code(&authorization.device_code)
+            .send()
+            .await;
+        let token = match result {
+            Ok(token) => token,
+            Err(error) => match error.as_service_error() {
+                Some(pending) if pending.is_authorization_pending_exception() => continue,
+                Some(slow_down) if slow_down.is_slow_down_exception() => {
+                    interval += Duration::from_secs(5);
+                    continue;
+                }
+                _ => return Err(AwsError::new("CreateToken", error)),
+            },
+        };
+
+        let expires_at = SystemTime::now() + Duration::from_secs(token.expires_in().max(0) as u64);
+        let mut cached = serde_json::json!({
+            "accessToken": token.access_token().unwrap_or_default(),
+            "expiresAt": format_time(expires_at),
+            "clientId": authorization.client_id,
+            "clientSecret": authorization.client_secret,
+            "registrationExpiresAt": format_time(authorization.client_expires_at),
+            "region": session.region,
+            "startUrl": session.start_url,
+        });
+        if let Some(refresh_token) = token.refresh_token() {
+            cached["refreshToken"] = refresh_token.into();
+        }
+
+        return write_cached_token(&session.cache_key, &cached.to_string());
+    }
+}
+
+fn oidc_client(config: &SdkConfig, session: &SsoSession) -> aws_sdk_ssooidc::Client {
+    let oidc_config = aws_sdk_ssooidc::config::Builder::from(config)
+        .region(Region::new(session.region.clone()))
+        .build();
+    aws_sdk_ssooidc::Client::from_conf(oidc_config)
+}
+
+fn format_time(time: SystemTime) -> String {
+    DateTime::from(time)
+        .fmt(DateTimeFormat::DateTime)
+        .unwrap_or_default()
+}
+
+// ~/.aws/sso/cache/<sha1 of the key>.json
+fn write_cached_token(cache_key: &str, contents: &str) -> Result<(), AwsError> {
+    let cache_error = |message: String| AwsError {
+        operation: "CreateToken".to_string(),
+        kind: ErrorKind::Other,
+        message,
+    };
+
+    let path = home_dir()
+        .map(|home| cache_path(&home.join(".aws").join("sso").join("cache"), cache_key))
+        .ok_or_else(|| cache_error("no home directory to cache the SSO token in".to_string()))?;
+    write_private(&path, contents)
+        .map_err(|error| cache_error(format!("{}: {error}", path.display())))
+}
+
+fn cache_path(dir: &Path, cache_key: &str) -> PathBuf {
+    let hash = Sha1::digest(cache_key.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    dir.join(format!("{hash}.json"))
+}
+
+// the token and client secret are only readable by the user, and the SDK
+// never reads a half written file since it is renamed into place
+fn write_private(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let temp = path.with_extension("json.tmp");
+    // a leftover of an earlier attempt keeps the permissions it had
+    let _ = fs::remove_file(&temp);
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let result = options
+        .open(&temp)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .and_then(|_| fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    fn settings(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn reads_sso_session_section() {
+        let profile = settings(&[
+            ("sso_session", "my-sso"),
+            ("sso_account_id", "123456789012"),
+        ]);
+        let session = SsoSession::from_settings(&profile, |name| match name {
+            "my-sso" => settings(&[
+                ("sso_start_url", "https://example.awsapps.com/start"),
+                ("sso_region", "eu-west-1"),
+            ]),
+            _ => HashMap::new(),
+        })
+        .unwrap();
+
+        assert_eq!(session.start_url, "https://example.awsapps.com/start");
+        assert_eq!(session.region, "eu-west-1");
+        assert_eq!(session.cache_key, "my-sso");
+
+        assert!(SsoSession::from_settings(&profile, |_| HashMap::new()).is_none());
+    }
+
+    #[test]
+    fn reads_legacy_profile() {
+        let profile = settings(&[
+            ("sso_start_url", "https://example.awsapps.com/start"),
+            ("sso_region", "us-east-1"),
+        ]);
+        let session = SsoSession::from_settings(&profile, |_| HashMap::new()).unwrap();
+
+        assert_eq!(session.region, "us-east-1");
+        assert_eq!(session.cache_key, "https://example.awsapps.com/start");
+
+        let profile = settings(&[("role_arn", "arn:aws:iam::123456789012:role/dev")]);
+        assert!(SsoSession::from_settings(&profile, |_| HashMap::new()).is_none());
+    }
+
+    // the file names `aws sso login` and the SDK use
+    #[test]
+    fn hashes_cache_key() {
+        let dir = Path::new("/home/dev/.aws/sso/cache");
+
+        assert_eq!(
+            cache_path(dir, "my-sso"),
+            dir.join("0ad374308c5a4e22f723adf10145eafad7c4031c.json")
+        );
+        assert_eq!(
+            cache_path(dir, "https://example.awsapps.com/start"),
+            dir.join("e8be5486177c5b5392bd9aa76563515b29358e6e.json")
+        );
+    }
+
+    #[test]
+    fn writes_token_readable_only_by_user() {
+        let dir = env::temp_dir().join(format!("raws-sso-{}", process::id()));
+        let path = cache_path(&dir, "my-sso");
+
+        write_private(&path, "{\"accessToken\": \"old\"}").unwrap();
+        write_private(&path, "{\"accessToken\": \"new\"}").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "{\"accessToken\": \"new\"}"
+        );
+        assert!(!path.with_extension("json.tmp").exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
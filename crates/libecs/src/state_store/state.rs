@@ -1,23 +1,494 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use aws_sdk_cloudwatchlogs::types::OutputLogEvent;
+use aws_sdk_ecs::{
+    primitives::DateTime,
+    types::{
+        Cluster, ClusterSettingName, Container, ContainerInstance, Deployment, ManagedAgentName,
+        Resource, Service, ServiceEvent, Task,
+    },
+};
+
+use crate::{
+    document::Value,
+    error::AwsError,
+    exec::ExecSession,
+    route::{resource_account, resource_region, DescribeTarget},
+};
+
 #[derive(Debug, Clone)]
 pub struct ClusterItem {
     pub arn: String,
     pub name: String,
+    pub region: String,
+    // account alias or ID
+    pub account: String,
+    pub status: String,
+    pub active_services_count: i32,
+    pub running_tasks_count: i32,
+    pub pending_tasks_count: i32,
+    pub registered_container_instances_count: i32,
+    pub container_insights: String,
+    // "<provider>(<weight>/<base>)" joined by ", "
+    pub capacity_provider_strategy: String,
+    pub execute_command_logging: String,
+    // "<key>=<value>"
+    pub tags: Vec<String>,
+}
+
+impl From<&Cluster> for ClusterItem {
+    fn from(cluster: &Cluster) -> Self {
+        Self {
+            arn: cluster.cluster_arn().unwrap_or_default().to_string(),
+            name: cluster.cluster_name().unwrap_or_default().to_string(),
+            region: cluster
+                .cluster_arn()
+                .and_then(resource_region)
+                .unwrap_or_default()
+                .to_string(),
+            account: cluster
+                .cluster_arn()
+                .and_then(resource_account)
+                .unwrap_or_default()
+                .to_string(),
+            status: cluster.status().unwrap_or_default().to_string(),
+            active_services_count: cluster.active_services_count(),
+            running_tasks_count: cluster.running_tasks_count(),
+            pending_tasks_count: cluster.pending_tasks_count(),
+            registered_container_instances_count: cluster.registered_container_instances_count(),
+            container_insights: cluster
+                .settings()
+                .iter()
+                .find(|setting| setting.name() == Some(&ClusterSettingName::ContainerInsights))
+                .and_then(|setting| setting.value())
+                .unwrap_or_default()
+                .to_string(),
+            capacity_provider_strategy: cluster
+                .default_capacity_provider_strategy()
+                .iter()
+                .map(|item| {
+                    format!(
+                        "{}({}/{})",
+                        item.capacity_provider(),
+                        item.weight(),
+                        item.base()
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join(", "),
+            execute_command_logging: cluster
+                .configuration()
+                .and_then(|configuration| configuration.execute_command_configuration())
+                .and_then(|configuration| configuration.logging())
+                .map(|logging| logging.to_string())
+                .unwrap_or_default(),
+            tags: cluster
+                .tags()
+                .iter()
+                .map(|tag| {
+                    format!(
+                        "{}={}",
+                        tag.key().unwrap_or_default(),
+                        tag.value().unwrap_or_default()
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceItem {
+    pub arn: String,
+    pub name: String,
+    pub status: String,
+    pub desired_count: i32,
+    pub running_count: i32,
+    pub pending_count: i32,
+    pub launch_type: String,
+    pub task_definition: String,
+    // (min, max) of the Application Auto Scaling target, when registered
+    pub scaling_range: Option<(i32, i32)>,
+}
+
+impl From<&Service> for ServiceItem {
+    fn from(service: &Service) -> Self {
+        Self {
+            arn: service.service_arn().unwrap_or_default().to_string(),
+            name: service.service_name().unwrap_or_default().to_string(),
+            status: service.status().unwrap_or_default().to_string(),
+            desired_count: service.desired_count(),
+            running_count: service.running_count(),
+            pending_count: service.pending_count(),
+            launch_type: service
+                .launch_type()
+                .map(|launch_type| launch_type.to_string())
+                .unwrap_or_default(),
+            // "arn:aws:ecs:<region>:<account>:task-definition/<family>:<revision>"
+            task_definition: service
+                .task_definition()
+                .and_then(|arn| arn.rsplit_once('/'))
+                .map(|(_, revision)| revision.to_string())
+                .unwrap_or_default(),
+            scaling_range: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DeploymentItem {
+    pub id: String,
+    // PRIMARY, ACTIVE or INACTIVE
+    pub status: String,
+    pub task_definition: String,
+    pub desired_count: i32,
+    pub running_count: i32,
+    pub pending_count: i32,
+    pub failed_tasks: i32,
+    // IN_PROGRESS, COMPLETED or FAILED, none for services without the
+    // rolling update deployment controller
+    pub rollout_state: Option<String>,
+    pub rollout_state_reason: String,
+    pub updated_at: Option<DateTime>,
+}
+
+impl DeploymentItem {
+    pub fn is_primary(&self) -> bool {
+        self.status == "PRIMARY"
+    }
 }
 
-impl From<String> for ClusterItem {
+impl From<&Deployment> for DeploymentItem {
+    fn from(deployment: &Deployment) -> Self {
+        Self {
+            id: deployment.id().unwrap_or_default().to_string(),
+            status: deployment.status().unwrap_or_default().to_string(),
+            task_definition: deployment
+                .task_definition()
+                .and_then(|arn| arn.rsplit_once('/'))
+                .map(|(_, revision)| revision.to_string())
+                .unwrap_or_default(),
+            desired_count: deployment.desired_count(),
+            running_count: deployment.running_count(),
+            pending_count: deployment.pending_count(),
+            failed_tasks: deployment.failed_tasks(),
+            rollout_state: deployment
+                .rollout_state()
+                .map(|rollout_state| rollout_state.to_string()),
+            rollout_state_reason: deployment
+                .rollout_state_reason()
+                .unwrap_or_default()
+                .to_string(),
+            updated_at: deployment.updated_at().cloned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ServiceEventItem {
+    pub id: String,
+    pub created_at: Option<DateTime>,
+    pub message: String,
+}
+
+impl From<&ServiceEvent> for ServiceEventItem {
+    fn from(event: &ServiceEvent) -> Self {
+        Self {
+            id: event.id().unwrap_or_default().to_string(),
+            created_at: event.created_at().cloned(),
+            message: event.message().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TaskKey {
+    pub cluster_arn: String,
+    pub service_name: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskItem {
+    pub arn: String,
+    pub id: String,
+    pub last_status: String,
+    pub desired_status: String,
+    pub health_status: String,
+    pub started_at: Option<DateTime>,
+    pub availability_zone: String,
+    pub cpu: String,
+    pub memory: String,
+    pub task_definition: String,
+    // "service:<name>" for tasks started by a service
+    pub group: String,
+}
+
+impl From<&Task> for TaskItem {
+    fn from(task: &Task) -> Self {
+        let arn = task.task_arn().unwrap_or_default().to_string();
+        // "arn:aws:ecs:<region>:<account>:task/<cluster>/<id>"
+        let id = arn
+            .rsplit_once('/')
+            .map(|(_, id)| id.to_string())
+            .unwrap_or_default();
+
+        Self {
+            arn,
+            id,
+            last_status: task.last_status().unwrap_or_default().to_string(),
+            desired_status: task.desired_status().unwrap_or_default().to_string(),
+            health_status: task
+                .health_status()
+                .map(|health_status| health_status.to_string())
+                .unwrap_or_default(),
+            started_at: task.started_at().cloned(),
+            availability_zone: task.availability_zone().unwrap_or_default().to_string(),
+            cpu: task.cpu().unwrap_or_default().to_string(),
+            memory: task.memory().unwrap_or_default().to_string(),
+            task_definition: task
+                .task_definition_arn()
+                .and_then(|arn| arn.rsplit_once('/'))
+                .map(|(_, revision)| revision.to_string())
+                .unwrap_or_default(),
+            group: task.group().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ContainerItem {
+    pub name: String,
+    pub image: String,
+    pub image_digest: String,
+    pub last_status: String,
+    pub exit_code: Option<i32>,
+    pub reason: String,
+    pub health_status: String,
+    pub runtime_id: String,
+    pub exec_agent_status: String,
+}
+
+impl From<&Container> for ContainerItem {
+    fn from(container: &Container) -> Self {
+        Self {
+            name: container.name().unwrap_or_default().to_string(),
+            image: container.image().unwrap_or_default().to_string(),
+            image_digest: container.image_digest().unwrap_or_default().to_string(),
+            last_status: container.last_status().unwrap_or_default().to_string(),
+            exit_code: container.exit_code(),
+            reason: container.reason().unwrap_or_default().to_string(),
+            health_status: container
+                .health_status()
+                .map(|health_status| health_status.to_string())
+                .unwrap_or_default(),
+            runtime_id: container.runtime_id().unwrap_or_default().to_string(),
+            exec_agent_status: container
+                .managed_agents()
+                .iter()
+                .find(|agent| agent.name() == Some(&ManagedAgentName::ExecuteCommandAgent))
+                .and_then(|agent| agent.last_status())
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LogKey {
+    pub task_arn: String,
+    pub container_name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogEventItem {
+    // milliseconds since the epoch
+    pub timestamp: Option<i64>,
+    pub message: String,
+}
+
+impl From<&OutputLogEvent> for LogEventItem {
+    fn from(event: &OutputLogEvent) -> Self {
+        Self {
+            timestamp: event.timestamp(),
+            message: event.message().unwrap_or_default().trim_end().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ContainerLogs {
+    // "<group> <stream>" once the stream was resolved
+    pub source: Option<String>,
+    // shared so the state and the logs view clone it without copying
+    pub events: Arc<Vec<LogEventItem>>,
+    // why the logs of the container cannot be read, e.g. another log driver
+    pub unavailable: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskDefinitionItem {
+    pub arn: String,
+    pub family: String,
+    pub revision: String,
+}
+
+impl From<String> for TaskDefinitionItem {
     fn from(arn: String) -> Self {
-        let (_, name) = arn.split_once('/').unwrap();
+        // "arn:aws:ecs:<region>:<account>:task-definition/<family>:<revision>"
+        let (family, revision) = arn
+            .rsplit_once('/')
+            .and_then(|(_, family_revision)| family_revision.rsplit_once(':'))
+            .map(|(family, revision)| (family.to_string(), revision.to_string()))
+            .unwrap_or_default();
+
+        Self {
+            arn,
+            family,
+            revision,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ContainerInstanceItem {
+    pub arn: String,
+    pub id: String,
+    pub ec2_instance_id: String,
+    pub status: String,
+    pub agent_connected: bool,
+    pub running_tasks_count: i32,
+    pub pending_tasks_count: i32,
+    pub agent_version: String,
+    // "<remaining>/<registered>"
+    pub cpu: String,
+    pub memory: String,
+}
+
+impl From<&ContainerInstance> for ContainerInstanceItem {
+    fn from(container_instance: &ContainerInstance) -> Self {
+        let arn = container_instance
+            .container_instance_arn()
+            .unwrap_or_default()
+            .to_string();
+        let id = arn
+            .rsplit_once('/')
+            .map(|(_, id)| id.to_string())
+            .unwrap_or_default();
+        let resource = |name: &str| {
+            let find = |resources: &[Resource]| {
+                resources
+                    .iter()
+                    .find(|resource| resource.name() == Some(name))
+                    .map(|resource| resource.integer_value())
+                    .unwrap_or_default()
+            };
+            format!(
+                "{}/{}",
+                find(container_instance.remaining_resources()),
+                find(container_instance.registered_resources())
+            )
+        };
+
         Self {
             arn: arn.clone(),
-            name: name.to_string(),
+            id,
+            ec2_instance_id: container_instance
+                .ec2_instance_id()
+                .unwrap_or_default()
+                .to_string(),
+            status: container_instance.status().unwrap_or_default().to_string(),
+            agent_connected: container_instance.agent_connected(),
+            running_tasks_count: container_instance.running_tasks_count(),
+            pending_tasks_count: container_instance.pending_tasks_count(),
+            agent_version: container_instance
+                .version_info()
+                .and_then(|version_info| version_info.agent_version())
+                .unwrap_or_default()
+                .to_string(),
+            cpu: resource("CPU"),
+            memory: resource("MEMORY"),
         }
     }
 }
 
+// list requests that are still fetching pages, rows already in the state
+// are shown while the remaining pages arrive
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Listing {
+    Clusters,
+    Services { cluster_arn: String },
+    Tasks(TaskKey),
+    TaskDefinitions { cluster_arn: Option<String> },
+    ContainerInstances { cluster_arn: String },
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct State {
+    pub profile: Option<String>,
+    pub region: Option<String>,
+    pub account: Option<String>,
     pub caller_arn: Option<String>,
-    pub cluster_arns: Vec<String>,
+    // profiles of the shared config files offered by the context switcher
+    pub profiles: Vec<String>,
+    pub clusters: Vec<ClusterItem>,
+    // (account and region, error) of the listings that failed
+    pub cluster_failures: Vec<(String, AwsError)>,
+    // services keyed by cluster ARN
+    pub services: HashMap<String, Vec<ServiceItem>>,
+    // deployments keyed by service ARN, the primary one first
+    pub deployments: HashMap<String, Vec<DeploymentItem>>,
+    // last events of a service keyed by service ARN, the newest first
+    pub service_events: HashMap<String, Vec<ServiceEventItem>>,
+    // latest task definition revisions of the family of a service, keyed by
+    // service ARN, the newest first
+    pub revisions: HashMap<String, Vec<TaskDefinitionItem>>,
+    pub tasks: HashMap<TaskKey, Vec<TaskItem>>,
+    // containers keyed by task ARN
+    pub containers: HashMap<String, Vec<ContainerItem>>,
+    // awslogs events of a container, the oldest first
+    pub logs: HashMap<LogKey, ContainerLogs>,
+    // task definitions keyed by the cluster ARN of the route, listed in its
+    // account and region
+    pub task_definitions: HashMap<Option<String>, Vec<TaskDefinitionItem>>,
+    // container instances keyed by cluster ARN
+    pub container_instances: HashMap<String, Vec<ContainerInstanceItem>>,
+    // full describe responses shown in the describe view
+    pub descriptions: HashMap<DescribeTarget, Value>,
+    pub loading: HashSet<Listing>,
+    // ARNs of the services scaled from the app that have not converged yet
+    pub scaling: HashSet<String>,
+    // ARNs of the services redeployed from the app whose rollout has not
+    // finished yet
+    pub deploying: HashSet<String>,
+    // failed AWS calls, the last one is shown until it is dismissed
+    pub errors: Vec<AwsError>,
+    // outcome of the last call that changed a resource, shown when there
+    // are no errors
+    pub notice: Option<String>,
+    // sign-in the failed actions wait for before they are retried
+    pub credential_prompt: Option<CredentialPrompt>,
+    // shell the app suspends itself for, until it sends `FinishExec`
+    pub exec_session: Option<ExecSession>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CredentialPrompt {
+    // token code of the MFA device of a profile or `--role`, `error` tells
+    // why the previous code was rejected
+    Mfa {
+        name: String,
+        serial: String,
+        error: Option<String>,
+    },
+    // device code confirmed in the browser while the SSO token is polled
+    Sso {
+        profile: String,
+        verification_uri: String,
+        user_code: String,
+    },
 }
 
 //impl Default for State {
@@ -1,60 +1,155 @@
-use aws_config::SdkConfig;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use aws_config::{BehaviorVersion, Region, SdkConfig};
+use aws_sdk_account::types::RegionOptStatus;
+use aws_sdk_applicationautoscaling::types::{ScalableDimension, ServiceNamespace};
+use aws_sdk_ecs::types::{
+    ClusterField, ContainerInstanceField, Service, ServiceField, SortOrder, TaskDefinitionField,
+    TaskField,
+};
 use color_eyre::Result;
-use tokio::sync::{
-    broadcast,
-    mpsc::{self, UnboundedReceiver, UnboundedSender},
+use tokio::{
+    sync::{
+        broadcast,
+        mpsc::{self, UnboundedReceiver, UnboundedSender},
+    },
+    task::JoinHandle,
+};
+
+use crate::{
+    document::ResponseBody,
+    error::{AwsError, ErrorKind},
+    exec::{exec_checks, ssm_endpoint, CheckStatus, ExecSession},
+    options::{AppOptions, Regions, RoleOptions},
+    route::{resource_account, resource_id, resource_name, resource_region, DescribeTarget, Route},
+    termination::{Interrupted, Terminator},
 };
 
-use crate::termination::{Interrupted, Terminator};
+use super::{
+    accounts::{assume_role, caller_account, Account},
+    action::Action,
+    logs::{log_events, LogSource},
+    profiles::{active_profile, profile_names, profile_settings},
+    sso::{start_device_authorization, wait_for_token, SsoSession},
+    state::{
+        ClusterItem, ContainerInstanceItem, ContainerItem, ContainerLogs, CredentialPrompt,
+        DeploymentItem, Listing, LogKey, ServiceEventItem, ServiceItem, State, TaskDefinitionItem,
+        TaskItem, TaskKey,
+    },
+};
+
+// scaled and redeployed services are described this often until they
+// settled
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const SCALING_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const DEPLOYMENT_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+// log events kept per container, the oldest are dropped first
+const MAX_LOG_EVENTS: usize = 10_000;
 
-use super::{action::Action, state::State};
+// sign-in the store waits for, actions are queued until it finishes and
+// `error` is shown if it is cancelled
+struct Login {
+    method: LoginMethod,
+    error: AwsError,
+}
+
+enum LoginMethod {
+    // the role of the profile itself, assumed with the credentials of its
+    // `source_profile`
+    ProfileMfa {
+        role: RoleOptions,
+        source_profile: String,
+    },
+    RoleMfa {
+        role: RoleOptions,
+    },
+    Sso {
+        task: JoinHandle<()>,
+    },
+}
 
 pub struct StateStore {
     config: SdkConfig,
+    profile: String,
+    regions: Regions,
+    roles: Vec<RoleOptions>,
+    // task definition revisions listed in the history of a service
+    revisions: i32,
+    // account of the config, resolved by the first cluster listing
+    caller: Option<Account>,
+    // accounts reached through `roles`, keyed by role ARN
+    assumed: HashMap<String, Account>,
+    login: Option<Login>,
+    // actions that failed or arrived during the sign-in
+    retry: Vec<Action>,
+    // when each service in `State::scaling` was scaled
+    scaling_since: HashMap<String, Instant>,
+    // when each service in `State::deploying` was redeployed
+    deploying_since: HashMap<String, Instant>,
+    // resolved stream of each container whose logs were read, with the
+    // token of the events read last
+    log_streams: HashMap<LogKey, (LogSource, Option<String>)>,
+    // actions the store sends itself, retries and finished SSO sign-ins
+    internal_tx: UnboundedSender<Action>,
+    internal_rx: UnboundedReceiver<Action>,
     state_tx: UnboundedSender<State>,
 }
 
 impl StateStore {
-    pub fn new(config: SdkConfig) -> (Self, UnboundedReceiver<State>) {
+    pub fn new(config: SdkConfig, options: AppOptions) -> (Self, UnboundedReceiver<State>) {
         let (state_tx, state_rx) = mpsc::unbounded_channel();
-        (StateStore { config, state_tx }, state_rx)
+        let (internal_tx, internal_rx) = mpsc::unbounded_channel();
+        (
+            StateStore {
+                config,
+                profile: active_profile(options.profile),
+                regions: options.regions,
+                roles: options.roles,
+                revisions: options.revisions,
+                caller: None,
+                assumed: HashMap::new(),
+                login: None,
+                retry: vec![],
+                scaling_since: HashMap::new(),
+                deploying_since: HashMap::new(),
+                log_streams: HashMap::new(),
+                internal_tx,
+                internal_rx,
+                state_tx,
+            },
+            state_rx,
+        )
     }
 
     pub async fn event_loop(
-        &self,
+        &mut self,
         mut terminator: Terminator,
         mut action_rx: UnboundedReceiver<Action>,
         mut interrupt_rx: broadcast::Receiver<Interrupted>,
     ) -> Result<Interrupted> {
-        let mut state = State::default();
+        let mut state = State {
+            profile: Some(self.profile.clone()),
+            region: self.config.region().map(ToString::to_string),
+            ..State::default()
+        };
 
         self.state_tx.send(state.clone())?;
 
         let result = loop {
             tokio::select! {
                 Some(action) = action_rx.recv() => match action {
-                    Action::GetContextInfo => {
-                        let sts_client = aws_sdk_sts::Client::new(&self.config);
-                        let caller_identity = sts_client.get_caller_identity().send().await.unwrap();
-
-                        state.caller_arn = caller_identity.clone().arn;
-
-                        self.state_tx.send(state.clone())?;
-                    }
-                    Action::GetClusters => {
-                        let ecs_client = aws_sdk_ecs::Client::new(&self.config);
-                        let clusters = ecs_client.list_clusters().send().await.unwrap();
-
-                        if let Some(clusters_arns) = clusters.cluster_arns {
-                            state.cluster_arns = clusters_arns.clone();
-                            self.state_tx.send(state.clone())?;
-                        }
-                    }
                     Action::Quit => {
                         let _ = terminator.terminate(Interrupted::UserInt);
                         break Interrupted::UserInt;
                     }
-                    _ => {}
+                    action => self.dispatch(&mut state, action).await?,
+                },
+                Some(action) = self.internal_rx.recv() => {
+                    self.dispatch(&mut state, action).await?;
                 },
                 Ok(interrupted) = interrupt_rx.recv() => {
                     break interrupted;
@@ -66,4 +161,1731 @@ impl StateStore {
 
         Ok(result)
     }
+
+    async fn dispatch(&mut self, state: &mut State, action: Action) -> Result<()> {
+        match action {
+            Action::DismissToast => {
+                if state.errors.pop().is_none() {
+                    state.notice = None;
+                }
+            }
+            Action::SubmitMfaCode { code } => self.submit_mfa_code(state, code).await?,
+            Action::CancelLogin => self.cancel_login(state),
+            Action::FinishSsoLogin(result) => self.finish_sso_login(state, result).await?,
+            // the next tick polls again once the user signed in
+            Action::Refresh(_) if self.login.is_some() => {}
+            // anything else needs the credentials being signed in for
+            action if self.login.is_some() => self.retry.push(action),
+            action => {
+                let failed = action.clone();
+                if let Err(report) = self.handle_action(state, action).await {
+                    // failed AWS calls are shown to the user, anything
+                    // else still stops the app
+                    let error = report.downcast::<AwsError>()?;
+                    // actions run one at a time so only the failed
+                    // listing can still be marked as loading
+                    state.loading.clear();
+                    if self.start_login(state, &error).await {
+                        self.retry.push(failed);
+                    } else if state.errors.last() != Some(&error) {
+                        // a refresh that keeps failing the same way is
+                        // shown once
+                        state.errors.push(error);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn handle_action(&mut self, state: &mut State, action: Action) -> Result<()> {
+        match action {
+            Action::GetContextInfo => self.get_context_info(state).await?,
+            Action::GetProfiles => state.profiles = profile_names(),
+            Action::SwitchContext { profile, region } => {
+                self.switch_context(state, profile, region).await?
+            }
+            Action::GetClusters => self.get_clusters(state).await?,
+            Action::GetServices { cluster_arn } => self.get_services(state, cluster_arn).await?,
+            Action::GetTasks {
+                cluster_arn,
+                service_name,
+            } => {
+                self.get_tasks(
+                    state,
+                    TaskKey {
+                        cluster_arn,
+                        service_name,
+                    },
+                )
+                .await?
+            }
+            Action::DescribeTask {
+                cluster_arn,
+                task_arn,
+            } => self.describe_task(state, cluster_arn, task_arn).await?,
+            Action::GetLogEvents {
+                cluster_arn,
+                task_arn,
+                container_name,
+            } => {
+                self.get_log_events(
+                    state,
+                    cluster_arn,
+                    LogKey {
+                        task_arn,
+                        container_name,
+                    },
+                )
+                .await?
+            }
+            Action::CloseLogs {
+                task_arn,
+                container_name,
+            } => {
+                let key = LogKey {
+                    task_arn,
+                    container_name,
+                };
+                state.logs.remove(&key);
+                self.log_streams.remove(&key);
+            }
+            Action::ExecuteCommand {
+                cluster_arn,
+                task_arn,
+                container_name,
+            } => {
+                self.execute_command(state, cluster_arn, task_arn, container_name)
+                    .await?
+            }
+            Action::FinishExec {
+                container_name,
+                result,
+            } => {
+                state.exec_session = None;
+                result?;
+                state.notice = Some(format!("shell in {container_name} closed"));
+            }
+            Action::GetTaskDefinitions { cluster_arn } => {
+                self.get_task_definitions(state, cluster_arn).await?
+            }
+            Action::GetContainerInstances { cluster_arn } => {
+                self.get_container_instances(state, cluster_arn).await?
+            }
+            Action::Describe(target) => self.describe(state, target).await?,
+            Action::Refresh(route) => self.refresh(state, route).await?,
+            Action::StopTask {
+                cluster_arn,
+                task_arn,
+                reason,
+            } => self.stop_task(state, cluster_arn, task_arn, reason).await?,
+            Action::ScaleService {
+                cluster_arn,
+                service_arn,
+                desired_count,
+            } => {
+                self.scale_service(state, cluster_arn, service_arn, desired_count)
+                    .await?
+            }
+            Action::TrackScaling {
+                cluster_arn,
+                service_arn,
+            } => self.track_scaling(state, cluster_arn, service_arn).await?,
+            Action::GetDeployments {
+                cluster_arn,
+                service_arn,
+            } => {
+                self.get_service_details(state, cluster_arn, service_arn)
+                    .await?
+            }
+            Action::ForceDeployment {
+                cluster_arn,
+                service_arn,
+            } => {
+                self.start_deployment(state, cluster_arn, service_arn, None)
+                    .await?
+            }
+            Action::GetServiceEvents {
+                cluster_arn,
+                service_arn,
+            } => {
+                self.get_service_details(state, cluster_arn, service_arn)
+                    .await?
+            }
+            Action::GetRevisions {
+                cluster_arn,
+                service_arn,
+            } => self.get_revisions(state, cluster_arn, service_arn).await?,
+            Action::RollbackService {
+                cluster_arn,
+                service_arn,
+                task_definition_arn,
+            } => {
+                self.start_deployment(state, cluster_arn, service_arn, Some(task_definition_arn))
+                    .await?
+            }
+            Action::TrackDeployment {
+                cluster_arn,
+                service_arn,
+            } => {
+                self.track_deployment(state, cluster_arn, service_arn)
+                    .await?
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    async fn get_context_info(&self, state: &mut State) -> Result<()> {
+        let sts_client = aws_sdk_sts::Client::new(&self.config);
+        let caller_identity = sts_client
+            .get_caller_identity()
+            .send()
+            .await
+            .map_err(|error| AwsError::new("GetCallerIdentity", error))?;
+
+        state.account = caller_identity.account;
+        state.caller_arn = caller_identity.arn;
+        Ok(())
+    }
+
+    // rebuild the config for another profile and region, overrides such as
+    // `--endpoint-url` are kept, everything listed for the previous context
+    // is dropped
+    async fn switch_context(
+        &mut self,
+        state: &mut State,
+        profile: String,
+        region: String,
+    ) -> Result<()> {
+        self.config = self.load_config(&profile, region.clone()).await;
+        self.profile = profile;
+        self.caller = None;
+        self.assumed.clear();
+
+        *state = State {
+            profile: Some(self.profile.clone()),
+            region: Some(region),
+            profiles: state.profiles.clone(),
+            errors: state.errors.clone(),
+            ..State::default()
+        };
+        self.state_tx.send(state.clone())?;
+
+        self.get_context_info(state).await?;
+        self.state_tx.send(state.clone())?;
+        self.get_clusters(state).await
+    }
+
+    // the same calls the screen of the route makes when it opens
+    async fn refresh(&mut self, state: &mut State, route: Route) -> Result<()> {
+        match route {
+            Route::Clusters => self.get_clusters(state).await,
+            Route::Services { cluster_arn } => self.get_services(state, cluster_arn).await,
+            Route::Tasks {
+                cluster_arn,
+                service_name,
+            } => {
+                self.get_tasks(
+                    state,
+                    TaskKey {
+                        cluster_arn,
+                        service_name,
+                    },
+                )
+                .await
+            }
+            Route::Containers {
+                cluster_arn,
+                task_arn,
+            } => self.describe_task(state, cluster_arn, task_arn).await,
+            Route::Logs {
+                cluster_arn,
+                task_arn,
+                container_name,
+            } => {
+                self.get_log_events(
+                    state,
+                    cluster_arn,
+                    LogKey {
+                        task_arn,
+                        container_name,
+                    },
+                )
+                .await
+            }
+            Route::Deployments {
+                cluster_arn,
+                service_arn,
+            } => {
+                self.get_service_details(state, cluster_arn, service_arn)
+                    .await
+            }
+            Route::History {
+                cluster_arn,
+                service_arn,
+            } => self.get_revisions(state, cluster_arn, service_arn).await,
+            Route::Events {
+                cluster_arn,
+                service_arn,
+            } => {
+                self.get_service_details(state, cluster_arn, service_arn)
+                    .await
+            }
+            Route::TaskDefinitions { cluster_arn } => {
+                self.get_task_definitions(state, cluster_arn).await
+            }
+            Route::ContainerInstances { cluster_arn } => {
+                self.get_container_instances(state, cluster_arn).await
+            }
+            Route::Describe { target } => self.describe(state, target).await,
+        }
+    }
+
+    // config of a profile that keeps overrides such as `--endpoint-url`
+    async fn load_config(&self, profile: &str, region: String) -> SdkConfig {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest())
+            .profile_name(profile)
+            .region(Region::new(region));
+        if let Some(endpoint_url) = self.config.endpoint_url() {
+            loader = loader.endpoint_url(endpoint_url);
+        }
+        loader.load().await
+    }
+
+    // the SDK neither asks for the token code of a profile with
+    // `mfa_serial` nor renews an expired SSO session, a call that fails for
+    // either opens a prompt and is retried once the user signed in
+    async fn start_login(&mut self, state: &mut State, error: &AwsError) -> bool {
+        if error.kind != ErrorKind::ExpiredCredentials {
+            return false;
+        }
+
+        let settings = profile_settings(&self.profile);
+        if let (Some(role_arn), Some(mfa_serial), Some(source_profile)) = (
+            settings.get("role_arn"),
+            settings.get("mfa_serial"),
+            settings.get("source_profile"),
+        ) {
+            let role = RoleOptions {
+                role_arn: role_arn.clone(),
+                external_id: settings.get("external_id").cloned(),
+                mfa_serial: Some(mfa_serial.clone()),
+            };
+            state.credential_prompt = Some(CredentialPrompt::Mfa {
+                name: self.profile.clone(),
+                serial: mfa_serial.clone(),
+                error: None,
+            });
+            self.login = Some(Login {
+                method: LoginMethod::ProfileMfa {
+                    role,
+                    source_profile: source_profile.clone(),
+                },
+                error: error.clone(),
+            });
+            return true;
+        }
+
+        let Some(session) = SsoSession::of_profile(&self.profile) else {
+            return false;
+        };
+        let authorization = match start_device_authorization(&self.config, &session).await {
+            Ok(authorization) => authorization,
+            Err(login_error) => {
+                state.errors.push(login_error);
+                return false;
+            }
+        };
+        state.credential_prompt = Some(CredentialPrompt::Sso {
+            profile: self.profile.clone(),
+            verification_uri: authorization.verification_uri.clone(),
+            user_code: authorization.user_code.clone(),
+        });
+
+        let config = self.config.clone();
+        let internal_tx = self.internal_tx.clone();
+        let task = tokio::spawn(async move {
+            let result = wait_for_token(&config, &session, authorization).await;
+            let _ = internal_tx.send(Action::FinishSsoLogin(result));
+        });
+        self.login = Some(Login {
+            method: LoginMethod::Sso { task },
+            error: error.clone(),
+        });
+        true
+    }
+
+    // a rejected code keeps the prompt open for another one
+    async fn submit_mfa_code(&mut self, state: &mut State, code: String) -> Result<()> {
+        let region = self
+            .config
+            .region()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        let (role, source_profile) = match self.login.as_ref().map(|login| &login.method) {
+            Some(LoginMethod::ProfileMfa {
+                role,
+                source_profile,
+            }) => (role.clone(), Some(source_profile.clone())),
+            Some(LoginMethod::RoleMfa { role }) => (role.clone(), None),
+            _ => return Ok(()),
+        };
+
+        let result = match source_profile {
+            Some(source_profile) => {
+                let source = self.load_config(&source_profile, region).await;
+                assume_role(&source, &role, Some(code))
+                    .await
+                    .map(|account| {
+                        self.config = account.config;
+                        self.caller = None;
+                    })
+            }
+            None => assume_role(&self.config, &role, Some(code))
+                .await
+                .map(|account| {
+                    self.assumed.insert(role.role_arn.clone(), account);
+                }),
+        };
+
+        match result {
+            Ok(()) => self.finish_login(state)?,
+            Err(error) => {
+                if let Some(CredentialPrompt::Mfa { error: message, .. }) =
+                    &mut state.credential_prompt
+                {
+                    *message = Some(error.message);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // the SDK picks up the cached token with a freshly loaded config
+    async fn finish_sso_login(
+        &mut self,
+        state: &mut State,
+        result: Result<(), AwsError>,
+    ) -> Result<()> {
+        match result {
+            Ok(()) => {
+                let region = self
+                    .config
+                    .region()
+                    .map(ToString::to_string)
+                    .unwrap_or_default();
+                self.config = self.load_config(&self.profile, region).await;
+                self.caller = None;
+                self.assumed.clear();
+                self.finish_login(state)
+            }
+            Err(error) => {
+                if let Some(login) = &mut self.login {
+                    login.error = error;
+                }
+                self.cancel_login(state);
+                Ok(())
+            }
+        }
+    }
+
+    fn finish_login(&mut self, state: &mut State) -> Result<()> {
+        self.login = None;
+        state.credential_prompt = None;
+        for action in self.retry.drain(..) {
+            self.internal_tx.send(action)?;
+        }
+        Ok(())
+    }
+
+    // the queued actions are dropped and the error that started the
+    // sign-in is shown instead
+    fn cancel_login(&mut self, state: &mut State) {
+        let Some(login) = self.login.take() else {
+            return;
+        };
+        if let LoginMethod::Sso { task } = login.method {
+            task.abort();
+        }
+        state.credential_prompt = None;
+        state.errors.push(login.error);
+        self.retry.clear();
+    }
+
+    async fn describe_task(
+        &self,
+        state: &mut State,
+        cluster_arn: String,
+        task_arn: String,
+    ) -> Result<()> {
+        let ecs_client = self.ecs_client_for(&cluster_arn);
+        let described = ecs_client
+            .describe_tasks()
+            .cluster(cluster_arn)
+            .tasks(task_arn.clone())
+            .send()
+            .await
+            .map_err(|error| AwsError::new("DescribeTasks", error))?;
+
+        if let Some(task) = described.tasks().first() {
+            let container_items = task.containers().iter().map(ContainerItem::from).collect();
+            state.containers.insert(task_arn, container_items);
+        }
+        Ok(())
+    }
+
+    // appends the events written since the last call, the stream is
+    // resolved from the task definition on the first one
+    async fn get_log_events(
+        &mut self,
+        state: &mut State,
+        cluster_arn: String,
+        key: LogKey,
+    ) -> Result<()> {
+        let (source, token) = match self.log_streams.get(&key) {
+            Some(stream) => stream.clone(),
+            None => match self.log_source(&cluster_arn, &key).await? {
+                Ok(source) => (source, None),
+                Err(reason) => {
+                    state.logs.insert(
+                        key,
+                        ContainerLogs {
+                            unavailable: Some(reason),
+                            ..ContainerLogs::default()
+                        },
+                    );
+                    return Ok(());
+                }
+            },
+        };
+
+        let logs_client = self.logs_client_for(&cluster_arn, source.region.as_deref());
+        let page = log_events(&logs_client, &source, token).await?;
+
+        let logs = state.logs.entry(key.clone()).or_default();
+        logs.source = Some(format!("{} {}", source.group, source.stream));
+        let events = Arc::make_mut(&mut logs.events);
+        events.extend(page.events);
+        let excess = events.len().saturating_sub(MAX_LOG_EVENTS);
+        events.drain(..excess);
+        self.log_streams.insert(key, (source, page.next_token));
+        Ok(())
+    }
+
+    // awslogs options of the container in the task definition of the task,
+    // or why its logs cannot be read
+    async fn log_source(
+        &self,
+        cluster_arn: &str,
+        key: &LogKey,
+    ) -> Result<std::result::Result<LogSource, String>> {
+        let ecs_client = self.ecs_client_for(cluster_arn);
+        let described = ecs_client
+            .describe_tasks()
+            .cluster(cluster_arn)
+            .tasks(key.task_arn.clone())
+            .send()
+            .await
+            .map_err(|error| AwsError::new("DescribeTasks", error))?;
+        let Some(task_definition_arn) = described
+            .tasks()
+            .first()
+            .and_then(|task| task.task_definition_arn())
+        else {
+            return Ok(Err("the task no longer exists".to_string()));
+        };
+
+        let described = ecs_client
+            .describe_task_definition()
+            .task_definition(task_definition_arn)
+            .send()
+            .await
+            .map_err(|error| AwsError::new("DescribeTaskDefinition", error))?;
+        let definition = described.task_definition().and_then(|task_definition| {
+            task_definition
+                .container_definitions()
+                .iter()
+                .find(|definition| definition.name() == Some(key.container_name.as_str()))
+        });
+
+        Ok(match definition {
+            Some(definition) => LogSource::of_container(definition, resource_id(&key.task_arn)),
+            None => Err(format!(
+                "{} is not part of the task definition",
+                key.container_name
+            )),
+        })
+    }
+
+    // each failed check is shown with the reason, the app runs the session
+    // manager plugin for the session it is handed
+    async fn execute_command(
+        &self,
+        state: &mut State,
+        cluster_arn: String,
+        task_arn: String,
+        container_name: String,
+    ) -> Result<()> {
+        let ecs_client = self.ecs_client_for(&cluster_arn);
+        let checks = exec_checks(
+            self.config_for(&cluster_arn),
+            &ecs_client,
+            &cluster_arn,
+            &task_arn,
+            Some(&container_name),
+        )
+        .await?;
+        let failures = checks
+            .into_iter()
+            .filter(|check| check.status == CheckStatus::Failed)
+            .map(|check| AwsError {
+                operation: "ExecuteCommand".to_string(),
+                kind: ErrorKind::Other,
+                message: format!(
+                    "cannot open a shell in {container_name}, {}: {}",
+                    check.name, check.value
+                ),
+            })
+            .collect::<Vec<_>>();
+        if !failures.is_empty() {
+            state.errors.extend(failures);
+            return Ok(());
+        }
+
+        let executed = ecs_client
+            .execute_command()
+            .cluster(&cluster_arn)
+            .task(&task_arn)
+            .container(&container_name)
+            .interactive(true)
+            .command("/bin/sh")
+            .send()
+            .await
+            .map_err(|error| AwsError::new("ExecuteCommand", error))?;
+        let Some(session) = executed.session() else {
+            return Err(AwsError {
+                operation: "ExecuteCommand".to_string(),
+                kind: ErrorKind::Other,
+                message: format!("no session returned for {container_name}"),
+            }
+            .into());
+        };
+
+        let runtime_id = state
+            .containers
+            .get(&task_arn)
+            .into_iter()
+            .flatten()
+            .find(|container| container.name == container_name)
+            .map(|container| container.runtime_id.clone())
+            .unwrap_or_default();
+        let region = resource_region(&cluster_arn)
+            .map(str::to_string)
+            .or(self.config.region().map(ToString::to_string))
+            .unwrap_or_default();
+        let endpoint = ssm_endpoint(self.config_for(&cluster_arn), &region).await?;
+        state.exec_session = Some(ExecSession {
+            session_id: session.session_id().unwrap_or_default().to_string(),
+            stream_url: session.stream_url().unwrap_or_default().to_string(),
+            token_value: session.token_value().unwrap_or_default().to_string(),
+            target: format!(
+                "ecs:{}_{}_{runtime_id}",
+                resource_name(&cluster_arn),
+                resource_id(&task_arn)
+            ),
+            region,
+            endpoint,
+            container_name,
+        });
+        Ok(())
+    }
+
+    // the task stays listed until the next refresh, with the desired status
+    // StopTask answered
+    async fn stop_task(
+        &self,
+        state: &mut State,
+        cluster_arn: String,
+        task_arn: String,
+        reason: Option<String>,
+    ) -> Result<()> {
+        let ecs_client = self.ecs_client_for(&cluster_arn);
+        let stopped = ecs_client
+            .stop_task()
+            .cluster(cluster_arn)
+            .task(task_arn.clone())
+            .set_reason(reason)
+            .send()
+            .await
+            .map_err(|error| AwsError::new("StopTask", error))?;
+
+        if let Some(task) = stopped.task() {
+            let stopped_item = TaskItem::from(task);
+            for task_item in state.tasks.values_mut().flatten() {
+                if task_item.arn == task_arn {
+                    task_item.desired_status = stopped_item.desired_status.clone();
+                    task_item.last_status = stopped_item.last_status.clone();
+                }
+            }
+        }
+        state.notice = Some(format!("stopping task {}", resource_id(&task_arn)));
+        Ok(())
+    }
+
+    // the service is polled until its tasks converged on the new count,
+    // whether or not its view is refreshed
+    async fn scale_service(
+        &mut self,
+        state: &mut State,
+        cluster_arn: String,
+        service_arn: String,
+        desired_count: i32,
+    ) -> Result<()> {
+        let ecs_client = self.ecs_client_for(&cluster_arn);
+        let updated = ecs_client
+            .update_service()
+            .cluster(cluster_arn.clone())
+            .service(service_arn.clone())
+            .desired_count(desired_count)
+            .send()
+            .await
+            .map_err(|error| AwsError::new("UpdateService", error))?;
+
+        if let Some(service) = updated.service() {
+            update_service_item(state, &cluster_arn, ServiceItem::from(service));
+        }
+        state.scaling.insert(service_arn.clone());
+        self.scaling_since
+            .insert(service_arn.clone(), Instant::now());
+        state.notice = Some(format!(
+            "scaling {} to {desired_count} tasks",
+            resource_id(&service_arn)
+        ));
+        self.send_later(Action::TrackScaling {
+            cluster_arn,
+            service_arn,
+        });
+        Ok(())
+    }
+
+    async fn track_scaling(
+        &mut self,
+        state: &mut State,
+        cluster_arn: String,
+        service_arn: String,
+    ) -> Result<()> {
+        let Some(service) = self
+            .describe_service(&cluster_arn, &service_arn)
+            .await?
+            .as_ref()
+            .map(ServiceItem::from)
+        else {
+            return Ok(());
+        };
+
+        let name = service.name.clone();
+        let converged =
+            service.running_count == service.desired_count && service.pending_count == 0;
+        let desired_count = service.desired_count;
+        update_service_item(state, &cluster_arn, service);
+
+        let since = self.scaling_since.get(&service_arn).copied();
+        if converged {
+            state.notice = Some(format!("{name} converged on {desired_count} running tasks"));
+        } else if since.is_some_and(|since| since.elapsed() > SCALING_TIMEOUT) {
+            state.notice = Some(format!(
+                "{name} has not converged on {desired_count} tasks after {} minutes",
+                SCALING_TIMEOUT.as_secs() / 60
+            ));
+        } else {
+            self.send_later(Action::TrackScaling {
+                cluster_arn,
+                service_arn,
+            });
+            return Ok(());
+        }
+
+        state.scaling.remove(&service_arn);
+        self.scaling_since.remove(&service_arn);
+        Ok(())
+    }
+
+    // deployments and events of a service, both come with its description
+    async fn get_service_details(
+        &self,
+        state: &mut State,
+        cluster_arn: String,
+        service_arn: String,
+    ) -> Result<()> {
+        if let Some(service) = self.describe_service(&cluster_arn, &service_arn).await? {
+            set_service_details(state, &cluster_arn, &service);
+        }
+        Ok(())
+    }
+
+    // the last revisions of the family of the task definition the service
+    // runs, the running one is kept even when newer ones were registered
+    async fn get_revisions(
+        &self,
+        state: &mut State,
+        cluster_arn: String,
+        service_arn: String,
+    ) -> Result<()> {
+        let Some(service) = self.describe_service(&cluster_arn, &service_arn).await? else {
+            return Ok(());
+        };
+        set_service_details(state, &cluster_arn, &service);
+        let Some(current) = service
+            .task_definition()
+            .map(|arn| TaskDefinitionItem::from(arn.to_string()))
+        else {
+            return Ok(());
+        };
+
+        let ecs_client = self.ecs_client_for(&cluster_arn);
+        let listed = ecs_client
+            .list_task_definitions()
+            .family_prefix(current.family.clone())
+            .sort(SortOrder::Desc)
+            .max_results(self.revisions)
+            .send()
+            .await
+            .map_err(|error| AwsError::new("ListTaskDefinitions", error))?;
+
+        let mut revision_items = listed
+            .task_definition_arns()
+            .iter()
+            .map(|arn| TaskDefinitionItem::from(arn.clone()))
+            .collect::<Vec<_>>();
+        if !revision_items.iter().any(|item| item.arn == current.arn) {
+            revision_items.push(current);
+        }
+        // revisions are numbers, the listing sorts them as such
+        revision_items.sort_by_key(|item| std::cmp::Reverse(item.revision.parse::<i64>().ok()));
+        state.revisions.insert(service_arn, revision_items);
+        Ok(())
+    }
+
+    // starts new tasks, of the current task definition to e.g. pick up
+    // rotated secrets or of a previous revision to roll back, the rollout
+    // is polled until it completed or failed
+    async fn start_deployment(
+        &mut self,
+        state: &mut State,
+        cluster_arn: String,
+        service_arn: String,
+        task_definition_arn: Option<String>,
+    ) -> Result<()> {
+        let ecs_client = self.ecs_client_for(&cluster_arn);
+        let update = ecs_client
+            .update_service()
+            .cluster(cluster_arn.clone())
+            .service(service_arn.clone());
+        let update = match &task_definition_arn {
+            Some(task_definition_arn) => update.task_definition(task_definition_arn),
+            None => update.force_new_deployment(true),
+        };
+        let updated = update
+            .send()
+            .await
+            .map_err(|error| AwsError::new("UpdateService", error))?;
+
+        if let Some(service) = updated.service() {
+            set_service_details(state, &cluster_arn, service);
+        }
+        state.deploying.insert(service_arn.clone());
+        self.deploying_since
+            .insert(service_arn.clone(), Instant::now());
+        state.notice = Some(match &task_definition_arn {
+            Some(task_definition_arn) => format!(
+                "rolling {} back to {}",
+                resource_id(&service_arn),
+                resource_name(task_definition_arn)
+            ),
+            None => format!("forcing a new deployment of {}", resource_id(&service_arn)),
+        });
+        self.send_later(Action::TrackDeployment {
+            cluster_arn,
+            service_arn,
+        });
+        Ok(())
+    }
+
+    async fn track_deployment(
+        &mut self,
+        state: &mut State,
+        cluster_arn: String,
+        service_arn: String,
+    ) -> Result<()> {
+        let Some(service) = self.describe_service(&cluster_arn, &service_arn).await? else {
+            return Ok(());
+        };
+        set_service_details(state, &cluster_arn, &service);
+
+        let name = resource_id(&service_arn).to_string();
+        // the described service is stored under the ARN of the response,
+        // one it does not match is not followed any further
+        let Some(deployments) = state.deployments.get(&service_arn) else {
+            state.deploying.remove(&service_arn);
+            self.deploying_since.remove(&service_arn);
+            return Ok(());
+        };
+        let primary = deployments
+            .iter()
+            .find(|deployment| deployment.is_primary());
+        // without a rollout state the deployment is done once the older
+        // ones drained and the new tasks are running
+        let rollout_state = match primary.and_then(|primary| primary.rollout_state.as_deref()) {
+            Some(rollout_state) => rollout_state,
+            None if deployments.len() == 1
+                && primary
+                    .is_some_and(|primary| primary.running_count == primary.desired_count) =>
+            {
+                "COMPLETED"
+            }
+            None => "IN_PROGRESS",
+        };
+
+        let since = self.deploying_since.get(&service_arn).copied();
+        match rollout_state {
+            "COMPLETED" => state.notice = Some(format!("{name} deployment completed")),
+            // not an AWS call failing, but shown the same way
+            "FAILED" => state.errors.push(AwsError {
+                operation: "UpdateService".to_string(),
+                kind: ErrorKind::Other,
+                message: format!(
+                    "{name} deployment failed: {}",
+                    primary
+                        .map(|primary| primary.rollout_state_reason.as_str())
+                        .unwrap_or_default()
+                ),
+            }),
+            _ if since.is_some_and(|since| since.elapsed() > DEPLOYMENT_TIMEOUT) => {
+                state.notice = Some(format!(
+                    "{name} deployment has not completed after {} minutes",
+                    DEPLOYMENT_TIMEOUT.as_secs() / 60
+                ))
+            }
+            _ => {
+                self.send_later(Action::TrackDeployment {
+                    cluster_arn,
+                    service_arn,
+                });
+                return Ok(());
+            }
+        }
+
+        state.deploying.remove(&service_arn);
+        self.deploying_since.remove(&service_arn);
+        Ok(())
+    }
+
+    async fn describe_service(
+        &self,
+        cluster_arn: &str,
+        service_arn: &str,
+    ) -> Result<Option<Service>> {
+        let ecs_client = self.ecs_client_for(cluster_arn);
+        let described = ecs_client
+            .describe_services()
+            .cluster(cluster_arn)
+            .services(service_arn)
+            .send()
+            .await
+            .map_err(|error| AwsError::new("DescribeServices", error))?;
+        Ok(described.services().first().cloned())
+    }
+
+    // sends an action to the store itself after the poll interval
+    fn send_later(&self, action: Action) {
+        let internal_tx = self.internal_tx.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            let _ = internal_tx.send(action);
+        });
+    }
+
+    async fn describe(&self, state: &mut State, target: DescribeTarget) -> Result<()> {
+        let ecs_client = self.ecs_client_for(target.scope_arn());
+        let body = ResponseBody::default();
+        let operation = match &target {
+            DescribeTarget::Cluster { cluster_arn } => {
+                ecs_client
+                    .describe_clusters()
+                    .clusters(cluster_arn)
+                    .include(ClusterField::Attachments)
+                    .include(ClusterField::Configurations)
+                    .include(ClusterField::Settings)
+                    .include(ClusterField::Statistics)
+                    .include(ClusterField::Tags)
+                    .customize()
+                    .interceptor(body.clone())
+                    .send()
+                    .await
+                    .map_err(|error| AwsError::new("DescribeClusters", error))?;
+                "DescribeClusters"
+            }
+            DescribeTarget::Service {
+                cluster_arn,
+                service_arn,
+            } => {
+                ecs_client
+                    .describe_services()
+                    .cluster(cluster_arn)
+                    .services(service_arn)
+                    .include(ServiceField::Tags)
+                    .customize()
+                    .interceptor(body.clone())
+                    .send()
+                    .await
+                    .map_err(|error| AwsError::new("DescribeServices", error))?;
+                "DescribeServices"
+            }
+            DescribeTarget::Task {
+                cluster_arn,
+                task_arn,
+            } => {
+                ecs_client
+                    .describe_tasks()
+                    .cluster(cluster_arn)
+                    .tasks(task_arn)
+                    .include(TaskField::Tags)
+                    .customize()
+                    .interceptor(body.clone())
+                    .send()
+                    .await
+                    .map_err(|error| AwsError::new("DescribeTasks", error))?;
+                "DescribeTasks"
+            }
+            DescribeTarget::TaskDefinition {
+                task_definition_arn,
+            } => {
+                ecs_client
+                    .describe_task_definition()
+                    .task_definition(task_definition_arn)
+                    .include(TaskDefinitionField::Tags)
+                    .customize()
+                    .interceptor(body.clone())
+                    .send()
+                    .await
+                    .map_err(|error| AwsError::new("DescribeTaskDefinition", error))?;
+                "DescribeTaskDefinition"
+            }
+            DescribeTarget::ContainerInstance {
+                cluster_arn,
+                container_instance_arn,
+            } => {
+                ecs_client
+                    .describe_container_instances()
+                    .cluster(cluster_arn)
+                    .container_instances(container_instance_arn)
+                    .include(ContainerInstanceField::Tags)
+                    .include(ContainerInstanceField::ContainerInstanceHealth)
+                    .customize()
+                    .interceptor(body.clone())
+                    .send()
+                    .await
+                    .map_err(|error| AwsError::new("DescribeContainerInstances", error))?;
+                "DescribeContainerInstances"
+            }
+        };
+        let description = body.value().map_err(|message| AwsError {
+            operation: operation.to_string(),
+            kind: ErrorKind::Other,
+            message,
+        })?;
+
+        state.descriptions.insert(target, description);
+        Ok(())
+    }
+
+    // the list handlers walk every page of the list call, each page is
+    // described and published right away so large accounts fill the table
+    // incrementally instead of after the last page, a refresh keeps the
+    // previous listing until every page is in so the table does not shrink
+
+    // clusters of every configured account and region are listed
+    // concurrently, one that fails is reported next to the clusters of the
+    // others
+    async fn get_clusters(&mut self, state: &mut State) -> Result<()> {
+        let refresh = !state.clusters.is_empty();
+        state.cluster_failures.clear();
+        if !refresh {
+            state.loading.insert(Listing::Clusters);
+            self.state_tx.send(state.clone())?;
+        }
+
+        let accounts = self.cluster_accounts(state).await?;
+        let regions = self.cluster_regions().await?;
+        let single_scope = self.roles.is_empty() && regions.len() == 1;
+
+        let (page_tx, mut page_rx) = mpsc::unbounded_channel();
+        for account in &accounts {
+            for region in &regions {
+                let scope = match self.roles.is_empty() {
+                    true => region.clone(),
+                    false => format!("{} {region}", account.label),
+                };
+                tokio::spawn(list_cluster_pages(
+                    ecs_client(&account.config, region),
+                    account.label.clone(),
+                    scope,
+                    page_tx.clone(),
+                ));
+            }
+        }
+        drop(page_tx);
+
+        let mut cluster_items = vec![];
+        while let Some((scope, page)) = page_rx.recv().await {
+            match page {
+                Ok(items) => cluster_items.extend(items),
+                Err(error) if single_scope => return Err(error.into()),
+                Err(error) => state.cluster_failures.push((scope, error)),
+            }
+
+            if !refresh {
+                state.clusters = cluster_items.clone();
+                self.state_tx.send(state.clone())?;
+            }
+        }
+        state.clusters = cluster_items;
+
+        state.loading.remove(&Listing::Clusters);
+        Ok(())
+    }
+
+    // the account of the config followed by the accounts of the roles that
+    // could be assumed, roles that fail are added to the cluster failures
+    // and the first one that needs a token code asks for it
+    async fn cluster_accounts(&mut self, state: &mut State) -> Result<Vec<Account>> {
+        let caller = match &self.caller {
+            Some(caller) => caller.clone(),
+            None => caller_account(&self.config).await?,
+        };
+        self.caller = Some(caller.clone());
+
+        let mut accounts = vec![caller];
+        for role in self.roles.clone() {
+            match self.assumed.get(&role.role_arn) {
+                Some(account) if !account.is_expired() => accounts.push(account.clone()),
+                _ => match assume_role(&self.config, &role, None).await {
+                    Ok(account) => {
+                        self.assumed.insert(role.role_arn.clone(), account.clone());
+                        accounts.push(account);
+                    }
+                    Err(error) => {
+                        if error.kind == ErrorKind::MfaRequired && self.login.is_none() {
+                            state.credential_prompt = Some(CredentialPrompt::Mfa {
+                                name: role.role_arn.clone(),
+                                serial: role.mfa_serial.clone().unwrap_or_default(),
+                                error: None,
+                            });
+                            self.login = Some(Login {
+                                method: LoginMethod::RoleMfa { role: role.clone() },
+                                error: error.clone(),
+                            });
+                            self.retry.push(Action::GetClusters);
+                        }
+                        state.cluster_failures.push((role.role_arn.clone(), error));
+                    }
+                },
+            }
+        }
+
+        Ok(accounts)
+    }
+
+    async fn cluster_regions(&self) -> Result<Vec<String>> {
+        match &self.regions {
+            Regions::Current => Ok(vec![self
+                .config
+                .region()
+                .map(ToString::to_string)
+                .unwrap_or_default()]),
+            Regions::List(regions) => Ok(regions.clone()),
+            Regions::AllEnabled => {
+                let account_client = aws_sdk_account::Client::new(&self.config);
+                let mut pages = account_client
+                    .list_regions()
+                    .region_opt_status_contains(RegionOptStatus::Enabled)
+                    .region_opt_status_contains(RegionOptStatus::EnabledByDefault)
+                    .into_paginator()
+                    .send();
+
+                let mut regions = vec![];
+                while let Some(page) = pages.next().await {
+                    let page = page.map_err(|error| AwsError::new("ListRegions", error))?;
+                    regions.extend(
+                        page.regions()
+                            .iter()
+                            .filter_map(|region| region.region_name())
+                            .map(str::to_string),
+                    );
+                }
+                Ok(regions)
+            }
+        }
+    }
+
+    // client for the account and region of a resource, which can differ
+    // from the ones of the config
+    fn ecs_client_for(&self, arn: &str) -> aws_sdk_ecs::Client {
+        ecs_client(
+            self.config_for(arn),
+            resource_region(arn).unwrap_or_default(),
+        )
+    }
+
+    fn autoscaling_client_for(&self, arn: &str) -> aws_sdk_applicationautoscaling::Client {
+        let mut config =
+            aws_sdk_applicationautoscaling::config::Builder::from(self.config_for(arn));
+        if let Some(region) = resource_region(arn) {
+            config = config.region(Region::new(region.to_string()));
+        }
+        aws_sdk_applicationautoscaling::Client::from_conf(config.build())
+    }
+
+    // log groups can live in another region than the cluster
+    fn logs_client_for(&self, arn: &str, region: Option<&str>) -> aws_sdk_cloudwatchlogs::Client {
+        let mut config = aws_sdk_cloudwatchlogs::config::Builder::from(self.config_for(arn));
+        if let Some(region) = region.or(resource_region(arn)) {
+            config = config.region(Region::new(region.to_string()));
+        }
+        aws_sdk_cloudwatchlogs::Client::from_conf(config.build())
+    }
+
+    fn config_for(&self, arn: &str) -> &SdkConfig {
+        resource_account(arn)
+            .and_then(|id| self.assumed.values().find(|account| account.id == id))
+            .map_or(&self.config, |account| &account.config)
+    }
+
+    // min and max of the services registered with Application Auto Scaling,
+    // the range is optional so a missing permission only leaves it out
+    async fn set_scaling_ranges(&self, cluster_arn: &str, service_items: &mut [ServiceItem]) {
+        let autoscaling_client = self.autoscaling_client_for(cluster_arn);
+        let cluster_name = resource_name(cluster_arn);
+        let resource_ids = service_items
+            .iter()
+            .map(|service| format!("service/{cluster_name}/{}", service.name))
+            .collect::<Vec<_>>();
+
+        // DescribeScalableTargets accepts at most 50 resources per call
+        for resource_ids in resource_ids.chunks(50) {
+            let Ok(targets) = autoscaling_client
+                .describe_scalable_targets()
+                .service_namespace(ServiceNamespace::Ecs)
+                .scalable_dimension(ScalableDimension::EcsServiceDesiredCount)
+                .set_resource_ids(Some(resource_ids.to_vec()))
+                .send()
+                .await
+            else {
+                return;
+            };
+
+            for target in targets.scalable_targets() {
+                let name = resource_id(target.resource_id());
+                if let Some(service) = service_items
+                    .iter_mut()
+                    .find(|service| service.name == name)
+                {
+                    service.scaling_range = Some((target.min_capacity(), target.max_capacity()));
+                }
+            }
+        }
+    }
+
+    async fn get_services(&self, state: &mut State, cluster_arn: String) -> Result<()> {
+        let ecs_client = self.ecs_client_for(&cluster_arn);
+        let mut pages = ecs_client
+            .list_services()
+            .cluster(cluster_arn.clone())
+            .into_paginator()
+            .send();
+
+        let listing = Listing::Services {
+            cluster_arn: cluster_arn.clone(),
+        };
+        let refresh = state.services.contains_key(&cluster_arn);
+        if !refresh {
+            state.loading.insert(listing.clone());
+            self.state_tx.send(state.clone())?;
+        }
+
+        let mut service_items = vec![];
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|error| AwsError::new("ListServices", error))?;
+            // DescribeServices accepts at most 10 services per call
+            for service_arns in page.service_arns().chunks(10) {
+                let described = ecs_client
+                    .describe_services()
+                    .cluster(cluster_arn.clone())
+                    .set_services(Some(service_arns.to_vec()))
+                    .send()
+                    .await
+                    .map_err(|error| AwsError::new("DescribeServices", error))?;
+
+                service_items.extend(described.services().iter().map(ServiceItem::from));
+            }
+
+            service_items.sort_by(|a, b| a.name.cmp(&b.name));
+            if !refresh {
+                state
+                    .services
+                    .insert(cluster_arn.clone(), service_items.clone());
+                self.state_tx.send(state.clone())?;
+            }
+        }
+        self.set_scaling_ranges(&cluster_arn, &mut service_items)
+            .await;
+        state.services.insert(cluster_arn, service_items);
+
+        state.loading.remove(&listing);
+        Ok(())
+    }
+
+    async fn get_tasks(&self, state: &mut State, key: TaskKey) -> Result<()> {
+        let ecs_client = self.ecs_client_for(&key.cluster_arn);
+        let mut pages = ecs_client
+            .list_tasks()
+            .cluster(key.cluster_arn.clone())
+            .set_service_name(key.service_name.clone())
+            .into_paginator()
+            .send();
+
+        let listing = Listing::Tasks(key.clone());
+        let refresh = state.tasks.contains_key(&key);
+        if !refresh {
+            state.loading.insert(listing.clone());
+            self.state_tx.send(state.clone())?;
+        }
+
+        let mut task_items = vec![];
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|error| AwsError::new("ListTasks", error))?;
+            // DescribeTasks accepts at most 100 tasks per call
+            for task_arns in page.task_arns().chunks(100) {
+                let described = ecs_client
+                    .describe_tasks()
+                    .cluster(key.cluster_arn.clone())
+                    .set_tasks(Some(task_arns.to_vec()))
+                    .send()
+                    .await
+                    .map_err(|error| AwsError::new("DescribeTasks", error))?;
+
+                task_items.extend(described.tasks().iter().map(TaskItem::from));
+            }
+
+            task_items.sort_by(|a, b| a.id.cmp(&b.id));
+            if !refresh {
+                state.tasks.insert(key.clone(), task_items.clone());
+                self.state_tx.send(state.clone())?;
+            }
+        }
+        state.tasks.insert(key, task_items);
+
+        state.loading.remove(&listing);
+        Ok(())
+    }
+
+    async fn get_task_definitions(
+        &self,
+        state: &mut State,
+        cluster_arn: Option<String>,
+    ) -> Result<()> {
+        let ecs_client = match &cluster_arn {
+            Some(cluster_arn) => self.ecs_client_for(cluster_arn),
+            None => aws_sdk_ecs::Client::new(&self.config),
+        };
+        let mut pages = ecs_client
+            .list_task_definitions()
+            .sort(SortOrder::Desc)
+            .into_paginator()
+            .send();
+
+        let listing = Listing::TaskDefinitions {
+            cluster_arn: cluster_arn.clone(),
+        };
+        let refresh = state.task_definitions.contains_key(&cluster_arn);
+        if !refresh {
+            state.loading.insert(listing.clone());
+            self.state_tx.send(state.clone())?;
+        }
+
+        let mut task_definition_items = vec![];
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|error| AwsError::new("ListTaskDefinitions", error))?;
+            task_definition_items.extend(
+                page.task_definition_arns()
+                    .iter()
+                    .map(|arn| TaskDefinitionItem::from(arn.clone())),
+            );
+
+            if !refresh {
+                state
+                    .task_definitions
+                    .insert(cluster_arn.clone(), task_definition_items.clone());
+                self.state_tx.send(state.clone())?;
+            }
+        }
+        state
+            .task_definitions
+            .insert(cluster_arn, task_definition_items);
+
+        state.loading.remove(&listing);
+        Ok(())
+    }
+
+    async fn get_container_instances(&self, state: &mut State, cluster_arn: String) -> Result<()> {
+        let ecs_client = self.ecs_client_for(&cluster_arn);
+        let mut pages = ecs_client
+            .list_container_instances()
+            .cluster(cluster_arn.clone())
+            .into_paginator()
+            .send();
+
+        let listing = Listing::ContainerInstances {
+            cluster_arn: cluster_arn.clone(),
+        };
+        let refresh = state.container_instances.contains_key(&cluster_arn);
+        if !refresh {
+            state.loading.insert(listing.clone());
+            self.state_tx.send(state.clone())?;
+        }
+
+        let mut container_instance_items = vec![];
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|error| AwsError::new("ListContainerInstances", error))?;
+            // DescribeContainerInstances accepts at most 100 instances per call
+            for container_instance_arns in page.container_instance_arns().chunks(100) {
+                let described = ecs_client
+                    .describe_container_instances()
+                    .cluster(cluster_arn.clone())
+                    .set_container_instances(Some(container_instance_arns.to_vec()))
+                    .send()
+                    .await
+                    .map_err(|error| AwsError::new("DescribeContainerInstances", error))?;
+
+                container_instance_items.extend(
+                    described
+                        .container_instances()
+                        .iter()
+                        .map(ContainerInstanceItem::from),
+                );
+            }
+
+            if !refresh {
+                state
+                    .container_instances
+                    .insert(cluster_arn.clone(), container_instance_items.clone());
+                self.state_tx.send(state.clone())?;
+            }
+        }
+        state
+            .container_instances
+            .insert(cluster_arn, container_instance_items);
+
+        state.loading.remove(&listing);
+        Ok(())
+    }
+}
+
+// the scaling range comes from Application Auto Scaling and is kept
+fn update_service_item(state: &mut State, cluster_arn: &str, service: ServiceItem) {
+    let item = state
+        .services
+        .get_mut(cluster_arn)
+        .and_then(|items| items.iter_mut().find(|item| item.arn == service.arn));
+    if let Some(item) = item {
+        *item = ServiceItem {
+            scaling_range: item.scaling_range,
+            ..service
+        };
+    }
+}
+
+// the service row is updated along with its deployments and events
+fn set_service_details(state: &mut State, cluster_arn: &str, service: &Service) {
+    let service_item = ServiceItem::from(service);
+    let deployment_items = service
+        .deployments()
+        .iter()
+        .map(DeploymentItem::from)
+        .collect();
+    state
+        .deployments
+        .insert(service_item.arn.clone(), deployment_items);
+    let mut event_items = service
+        .events()
+        .iter()
+        .map(ServiceEventItem::from)
+        .collect::<Vec<_>>();
+    event_items.sort_by_key(|item| std::cmp::Reverse(item.created_at.map(|at| at.secs())));
+    state
+        .service_events
+        .insert(service_item.arn.clone(), event_items);
+    update_service_item(state, cluster_arn, service_item);
+}
+
+// an empty region keeps the one of the config
+fn ecs_client(config: &SdkConfig, region: &str) -> aws_sdk_ecs::Client {
+    let mut config = aws_sdk_ecs::config::Builder::from(config);
+    if !region.is_empty() {
+        config = config.region(Region::new(region.to_string()));
+    }
+    aws_sdk_ecs::Client::from_conf(config.build())
+}
+
+// (account and region, clusters of one page)
+type ClusterPage = (String, std::result::Result<Vec<ClusterItem>, AwsError>);
+
+// walks the clusters of one account and region, stops at the first failed
+// call
+async fn list_cluster_pages(
+    ecs_client: aws_sdk_ecs::Client,
+    account: String,
+    scope: String,
+    page_tx: UnboundedSender<ClusterPage>,
+) {
+    let mut pages = ecs_client.list_clusters().into_paginator().send();
+
+    while let Some(page) = pages.next().await {
+        let page = match page {
+            Ok(page) => describe_clusters(&ecs_client, page.cluster_arns())
+                .await
+                .map(|items| {
+                    items
+                        .into_iter()
+                        .map(|item| ClusterItem {
+                            account: account.clone(),
+                            ..item
+                        })
+                        .collect()
+                }),
+            Err(error) => Err(AwsError::new("ListClusters", error)),
+        };
+        let failed = page.is_err();
+
+        if page_tx.send((scope.clone(), page)).is_err() || failed {
+            break;
+        }
+    }
+}
+
+async fn describe_clusters(
+    ecs_client: &aws_sdk_ecs::Client,
+    cluster_arns: &[String],
+) -> std::result::Result<Vec<ClusterItem>, AwsError> {
+    let mut cluster_items = vec![];
+    // DescribeClusters accepts at most 100 clusters per call
+    for cluster_arns in cluster_arns.chunks(100) {
+        let described = ecs_client
+            .describe_clusters()
+            .set_clusters(Some(cluster_arns.to_vec()))
+            .include(ClusterField::Configurations)
+            .include(ClusterField::Settings)
+            .include(ClusterField::Statistics)
+            .include(ClusterField::Tags)
+            .send()
+            .await
+            .map_err(|error| AwsError::new("DescribeClusters", error))?;
+
+        cluster_items.extend(described.clusters().iter().map(ClusterItem::from));
+    }
+    Ok(cluster_items)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    const CONFIG: &str = "\
+[profile dev]
+role_arn = arn:aws:iam::123456789012:role/dev
+source_profile = default
+mfa_serial = arn:aws:iam::123456789012:mfa/dev
+";
+
+    // every test points the SDK at the same files, so setting them again
+    // does not race
+    fn store(profile: &str) -> StateStore {
+        let dir = env::temp_dir().join(format!("raws-store-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("config"), CONFIG).unwrap();
+        env::set_var("AWS_CONFIG_FILE", dir.join("config"));
+        env::set_var("AWS_SHARED_CREDENTIALS_FILE", dir.join("credentials"));
+
+        let config = SdkConfig::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-east-1"))
+            .build();
+        let options = AppOptions {
+            profile: Some(profile.to_string()),
+            ..AppOptions::default()
+        };
+        StateStore::new(config, options).0
+    }
+
+    fn error(kind: ErrorKind) -> AwsError {
+        AwsError {
+            operation: "ListClusters".to_string(),
+            kind,
+            message: "the security token included in the request is expired".to_string(),
+        }
+    }
+
+    // a handler that fails without calling AWS
+    fn failing(error: &AwsError) -> Action {
+        Action::FinishExec {
+            container_name: "web".to_string(),
+            result: Err(error.clone()),
+        }
+    }
+
+    fn sent(store: &mut StateStore) -> Vec<Action> {
+        let mut actions = vec![];
+        while let Ok(action) = store.internal_rx.try_recv() {
+            actions.push(action);
+        }
+        actions
+    }
+
+    #[tokio::test]
+    async fn retries_failed_action_after_mfa() {
+        let mut store = store("dev");
+        let mut state = State::default();
+        let expired = error(ErrorKind::ExpiredCredentials);
+
+        store.dispatch(&mut state, failing(&expired)).await.unwrap();
+        assert_eq!(
+            state.credential_prompt,
+            Some(CredentialPrompt::Mfa {
+                name: "dev".to_string(),
+                serial: "arn:aws:iam::123456789012:mfa/dev".to_string(),
+                error: None,
+            })
+        );
+        assert!(state.errors.is_empty());
+
+        // actions of the sign-in go through, the rest wait for it
+        store
+            .dispatch(&mut state, Action::GetClusters)
+            .await
+            .unwrap();
+        store
+            .dispatch(&mut state, Action::Refresh(Route::Clusters))
+            .await
+            .unwrap();
+        assert!(sent(&mut store).is_empty());
+
+        store.finish_login(&mut state).unwrap();
+        assert_eq!(state.credential_prompt, None);
+        assert_eq!(sent(&mut store), [failing(&expired), Action::GetClusters]);
+    }
+
+    #[tokio::test]
+    async fn shows_error_when_mfa_is_cancelled() {
+        let mut store = store("dev");
+        let mut state = State::default();
+        let expired = error(ErrorKind::ExpiredCredentials);
+
+        store.dispatch(&mut state, failing(&expired)).await.unwrap();
+        store
+            .dispatch(&mut state, Action::GetClusters)
+            .await
+            .unwrap();
+        store
+            .dispatch(&mut state, Action::CancelLogin)
+            .await
+            .unwrap();
+
+        assert_eq!(state.credential_prompt, None);
+        assert_eq!(state.errors, [expired]);
+        assert!(store.retry.is_empty());
+        assert!(sent(&mut store).is_empty());
+    }
+
+    #[tokio::test]
+    async fn shows_error_when_sso_sign_in_fails() {
+        let mut store = store("sso");
+        let mut state = State::default();
+        let task = tokio::spawn(futures::future::pending());
+        state.credential_prompt = Some(CredentialPrompt::Sso {
+            profile: "sso".to_string(),
+            verification_uri: "https://device.sso.eu-west-1.amazonaws.com/".to_string(),
+            user_code: "ABCD-EFGH".to_string(),
+        });
+        store.login = Some(Login {
+            method: LoginMethod::Sso { task },
+            error: error(ErrorKind::ExpiredCredentials),
+        });
+        store.retry.push(Action::GetClusters);
+
+        let denied = error(ErrorKind::AccessDenied);
+        store
+            .dispatch(&mut state, Action::FinishSsoLogin(Err(denied.clone())))
+            .await
+            .unwrap();
+
+        assert!(store.login.is_none());
+        assert_eq!(state.credential_prompt, None);
+        assert_eq!(state.errors, [denied]);
+        assert!(store.retry.is_empty());
+    }
+
+    #[tokio::test]
+    async fn shows_other_errors_without_sign_in() {
+        let mut store = store("dev");
+        let mut state = State::default();
+        let denied = error(ErrorKind::AccessDenied);
+
+        store.dispatch(&mut state, failing(&denied)).await.unwrap();
+        // a refresh that keeps failing the same way is shown once
+        store.dispatch(&mut state, failing(&denied)).await.unwrap();
+
+        assert_eq!(state.credential_prompt, None);
+        assert_eq!(state.errors, [denied]);
+        assert!(store.retry.is_empty());
+    }
+
+    #[tokio::test]
+    async fn drops_logs_of_closed_container() {
+        let mut store = store("default");
+        let mut state = State::default();
+        let key = |container_name: &str| LogKey {
+            task_arn: "arn:aws:ecs:us-east-1:123456789012:task/demo/task00".to_string(),
+            container_name: container_name.to_string(),
+        };
+        for container_name in ["web", "sidecar"] {
+            state
+                .logs
+                .insert(key(container_name), ContainerLogs::default());
+            let source = LogSource {
+                group: "/ecs/demo".to_string(),
+                stream: format!("ecs/{container_name}/task00"),
+                region: None,
+            };
+            store
+                .log_streams
+                .insert(key(container_name), (source, Some("token".to_string())));
+        }
+
+        let close = Action::CloseLogs {
+            task_arn: key("web").task_arn,
+            container_name: "web".to_string(),
+        };
+        store.dispatch(&mut state, close).await.unwrap();
+
+        assert_eq!(state.logs.keys().collect::<Vec<_>>(), [&key("sidecar")]);
+        assert_eq!(
+            store.log_streams.keys().collect::<Vec<_>>(),
+            [&key("sidecar")]
+        );
+    }
 }
@@ -0,0 +1,212 @@
+use std::{
+    io::{self, stdout},
+    panic,
+    sync::{Mutex, PoisonError},
+};
+
+use color_eyre::{config::HookBuilder, Result};
+use ratatui::{
+    backend::Backend,
+    crossterm::{
+        terminal::{
+            disable_raw_mode, enable_raw_mode, is_raw_mode_enabled, EnterAlternateScreen,
+            LeaveAlternateScreen,
+        },
+        ExecutableCommand,
+    },
+    Terminal,
+};
+
+// switches the terminal in and out of the modes the app draws in, faked in
+// tests so the restore can be checked without a tty
+pub trait TerminalMode {
+    fn enter(&mut self) -> io::Result<()>;
+    fn leave(&mut self) -> io::Result<()>;
+}
+
+pub struct Crossterm;
+
+impl TerminalMode for Crossterm {
+    fn enter(&mut self) -> io::Result<()> {
+        stdout().execute(EnterAlternateScreen)?;
+        enable_raw_mode()
+    }
+
+    // safe to call more than once, the panic hook and the guard both restore
+    fn leave(&mut self) -> io::Result<()> {
+        if is_raw_mode_enabled()? {
+            disable_raw_mode()?;
+            stdout().execute(LeaveAlternateScreen)?;
+        }
+        Ok(())
+    }
+}
+
+// owns the terminal for the lifetime of the app and restores it when
+// dropped, including on early returns and while unwinding from a panic
+pub struct TerminalGuard<B: Backend, M: TerminalMode> {
+    terminal: Terminal<B>,
+    mode: M,
+}
+
+impl<B: Backend, M: TerminalMode> TerminalGuard<B, M> {
+    pub fn new(backend: B, mut mode: M) -> Result<Self> {
+        mode.enter()?;
+        let terminal = match Terminal::new(backend) {
+            Ok(terminal) => terminal,
+            Err(error) => {
+                let _ = mode.leave();
+                return Err(error.into());
+            }
+        };
+
+        Ok(Self { terminal, mode })
+    }
+
+    pub fn terminal(&mut self) -> &mut Terminal<B> {
+        &mut self.terminal
+    }
+}
+
+impl<B: Backend, M: TerminalMode> Drop for TerminalGuard<B, M> {
+    fn drop(&mut self) {
+        let _ = self.terminal.show_cursor();
+        let _ = self.mode.leave();
+    }
+}
+
+// panics are reported by the hook before the stack unwinds to the guard, so
+// the panic hook restores the terminal itself before color_eyre prints. the
+// eyre hook is left alone, it runs whenever a report is built, and the app
+// builds one for every failed AWS call it shows as a toast
+pub fn install_hooks<M: TerminalMode + Send + 'static>(mode: M) -> Result<()> {
+    let (panic_hook, eyre_hook) = HookBuilder::default().into_hooks();
+
+    let panic_hook = panic_hook.into_panic_hook();
+    let mode = Mutex::new(mode);
+    panic::set_hook(Box::new(move |panic_info| {
+        let _ = mode.lock().unwrap_or_else(PoisonError::into_inner).leave();
+        panic_hook(panic_info);
+    }));
+
+    eyre_hook.install()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic::{catch_unwind, AssertUnwindSafe},
+        sync::Arc,
+        thread::{self, ThreadId},
+    };
+
+    use color_eyre::eyre;
+    use ratatui::{backend::TestBackend, widgets::Paragraph};
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct FakeMode {
+        calls: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl FakeMode {
+        fn calls(&self) -> Vec<&'static str> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl TerminalMode for FakeMode {
+        fn enter(&mut self) -> io::Result<()> {
+            self.calls.lock().unwrap().push("enter");
+            Ok(())
+        }
+
+        fn leave(&mut self) -> io::Result<()> {
+            self.calls.lock().unwrap().push("leave");
+            Ok(())
+        }
+    }
+
+    // the panic hook is process wide, so a test panicking on another thread
+    // must not count as a restore of this one
+    struct ThreadMode {
+        mode: FakeMode,
+        thread: ThreadId,
+    }
+
+    impl TerminalMode for ThreadMode {
+        fn enter(&mut self) -> io::Result<()> {
+            self.mode.enter()
+        }
+
+        fn leave(&mut self) -> io::Result<()> {
+            match thread::current().id() == self.thread {
+                true => self.mode.leave(),
+                false => Ok(()),
+            }
+        }
+    }
+
+    fn draw_then_fail(mode: FakeMode) -> Result<()> {
+        let mut guard = TerminalGuard::new(TestBackend::new(20, 2), mode)?;
+        guard
+            .terminal()
+            .draw(|frame| frame.render_widget(Paragraph::new("raws"), frame.size()))?;
+
+        Err(eyre::eyre!("failed while the app was running"))
+    }
+
+    #[test]
+    fn restores_terminal_on_drop() {
+        let mode = FakeMode::default();
+
+        {
+            let mut guard = TerminalGuard::new(TestBackend::new(20, 2), mode.clone()).unwrap();
+            guard.terminal().hide_cursor().unwrap();
+            assert_eq!(mode.calls(), ["enter"]);
+        }
+
+        assert_eq!(mode.calls(), ["enter", "leave"]);
+    }
+
+    #[test]
+    fn restores_terminal_on_error() {
+        let mode = FakeMode::default();
+
+        assert!(draw_then_fail(mode.clone()).is_err());
+        assert_eq!(mode.calls(), ["enter", "leave"]);
+    }
+
+    #[test]
+    fn restores_terminal_on_panic() {
+        let mode = FakeMode::default();
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            let _guard = TerminalGuard::new(TestBackend::new(20, 2), mode.clone()).unwrap();
+            panic!("panicked while the app was running");
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(mode.calls(), ["enter", "leave"]);
+    }
+
+    #[test]
+    fn keeps_terminal_while_reporting_errors() {
+        let mode = FakeMode::default();
+        // a test that built a report first installed the default eyre hook,
+        // the panic hook is installed either way
+        let _ = install_hooks(ThreadMode {
+            mode: mode.clone(),
+            thread: thread::current().id(),
+        });
+
+        let _guard = TerminalGuard::new(TestBackend::new(20, 2), mode.clone()).unwrap();
+        let report = eyre::eyre!("failed to list the clusters");
+
+        assert_eq!(report.to_string(), "failed to list the clusters");
+        assert_eq!(mode.calls(), ["enter"]);
+    }
+}
@@ -23,6 +23,74 @@ impl<'a> Default for KeybindingsWidget<'a> {
                     binding: "<q>",
                     description: "Quit",
                 },
+                KeybindingItem {
+                    binding: "<enter>",
+                    description: "Open",
+                },
+                KeybindingItem {
+                    binding: "<esc>",
+                    description: "Back",
+                },
+                KeybindingItem {
+                    binding: "<:>",
+                    description: "Command",
+                },
+                KeybindingItem {
+                    binding: "</>",
+                    description: "Filter",
+                },
+                KeybindingItem {
+                    binding: "<p>",
+                    description: "Context",
+                },
+                KeybindingItem {
+                    binding: "<d>",
+                    description: "Describe",
+                },
+                KeybindingItem {
+                    binding: "<s/S>",
+                    description: "Sort",
+                },
+                KeybindingItem {
+                    binding: "<ctrl-s>",
+                    description: "Scale service",
+                },
+                KeybindingItem {
+                    binding: "<r>",
+                    description: "Redeploy",
+                },
+                KeybindingItem {
+                    binding: "<e>",
+                    description: "Events",
+                },
+                KeybindingItem {
+                    binding: "<h>",
+                    description: "History",
+                },
+                KeybindingItem {
+                    binding: "<ctrl-r>",
+                    description: "Roll back",
+                },
+                KeybindingItem {
+                    binding: "<l>",
+                    description: "Logs",
+                },
+                KeybindingItem {
+                    binding: "<s>",
+                    description: "Shell",
+                },
+                KeybindingItem {
+                    binding: "<ctrl-k>",
+                    description: "Stop task",
+                },
+                KeybindingItem {
+                    binding: "<R>",
+                    description: "Refresh",
+                },
+                KeybindingItem {
+                    binding: "<a>",
+                    description: "Auto refresh",
+                },
                 //KeybindingItem {
                 //    binding: "<j>",
                 //    description: "Move down",
@@ -44,7 +112,7 @@ impl<'a> Widget for KeybindingsWidget<'a> {
         let outer_area = area;
 
         let [keybinding_area, description_area] =
-            Layout::horizontal([Constraint::Length(4), Constraint::Percentage(100)])
+            Layout::horizontal([Constraint::Length(9), Constraint::Percentage(100)])
                 .areas(outer_area);
 
         let keybinding_items: Vec<ListItem> = self
@@ -0,0 +1,51 @@
+use std::{error, fmt, io};
+
+use tokio_tungstenite::tungstenite;
+
+// failure of a session, the agent closing the channel is not one
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    // boxed, the error is large and most results never hold one
+    WebSocket(Box<tungstenite::Error>),
+    // a frame or payload that does not follow the protocol
+    Protocol(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "io error: {error}"),
+            Error::WebSocket(error) => write!(f, "websocket error: {error}"),
+            Error::Protocol(message) => write!(f, "protocol error: {message}"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            Error::WebSocket(error) => Some(error.as_ref()),
+            Error::Protocol(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<tungstenite::Error> for Error {
+    fn from(error: tungstenite::Error) -> Self {
+        Error::WebSocket(Box::new(error))
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::Protocol(format!("invalid payload, {error}"))
+    }
+}
@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// reported to the agent, the version of the Go plugin this client follows
+pub const CLIENT_VERSION: &str = "1.2.650.0";
+
+// session types a terminal can drive, port forwarding is not one
+const SESSION_TYPES: [&str; 2] = ["Standard_Stream", "InteractiveCommands"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "u32", from = "u32")]
+pub enum ActionStatus {
+    Success,
+    Failed,
+    Unsupported,
+}
+
+impl From<ActionStatus> for u32 {
+    fn from(status: ActionStatus) -> Self {
+        match status {
+            ActionStatus::Success => 1,
+            ActionStatus::Failed => 2,
+            ActionStatus::Unsupported => 3,
+        }
+    }
+}
+
+impl From<u32> for ActionStatus {
+    fn from(value: u32) -> Self {
+        match value {
+            1 => ActionStatus::Success,
+            2 => ActionStatus::Failed,
+            _ => ActionStatus::Unsupported,
+        }
+    }
+}
+
+// first payload of the agent, the actions the client has to agree to
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct HandshakeRequest {
+    #[serde(default)]
+    pub agent_version: String,
+    #[serde(default)]
+    pub requested_client_actions: Vec<RequestedClientAction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RequestedClientAction {
+    pub action_type: String,
+    #[serde(default)]
+    pub action_parameters: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct HandshakeResponse {
+    pub client_version: String,
+    pub processed_client_actions: Vec<ProcessedClientAction>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ProcessedClientAction {
+    pub action_type: String,
+    pub action_status: ActionStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_result: Option<Value>,
+    #[serde(default)]
+    pub error: String,
+}
+
+// sent once the agent accepted the response, the message is shown before
+// the shell starts
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct HandshakeComplete {
+    #[serde(default)]
+    pub handshake_time_to_complete: i64,
+    #[serde(default)]
+    pub customer_message: String,
+}
+
+// agrees to the shell session types and turns down the rest, KMS
+// encryption of the channel is not implemented so the agent ends those
+// sessions with the error
+pub fn respond(request: &HandshakeRequest) -> HandshakeResponse {
+    let mut errors = vec![];
+    let processed_client_actions = request
+        .requested_client_actions
+        .iter()
+        .map(|action| {
+            let (status, error) = match action.action_type.as_str() {
+                "SessionType" => {
+                    let session_type = action
+                        .action_parameters
+                        .get("SessionType")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default();
+                    match SESSION_TYPES.contains(&session_type) {
+                        true => (ActionStatus::Success, String::new()),
+                        false => (
+                            ActionStatus::Unsupported,
+                            format!("session type {session_type} is not supported"),
+                        ),
+                    }
+                }
+                "KMSEncryption" => (
+                    ActionStatus::Failed,
+                    "KMS encryption of the session is not supported".to_string(),
+                ),
+                action_type => (
+                    ActionStatus::Unsupported,
+                    format!("action {action_type} is not supported"),
+                ),
+            };
+            if !error.is_empty() {
+                errors.push(error.clone());
+            }
+            ProcessedClientAction {
+                action_type: action.action_type.clone(),
+                action_status: status,
+                action_result: None,
+                error,
+            }
+        })
+        .collect();
+
+    HandshakeResponse {
+        client_version: CLIENT_VERSION.to_string(),
+        processed_client_actions,
+        errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn request(actions: Value) -> HandshakeRequest {
+        serde_json::from_value(json!({
+            "AgentVersion": "3.3.418.0",
+            "RequestedClientActions": actions,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn accepts_shell_sessions() {
+        let response = respond(&request(json!([{
+            "ActionType": "SessionType",
+            "ActionParameters": {"SessionType": "InteractiveCommands", "Properties": null},
+        }])));
+
+        assert!(response.errors.is_empty());
+        assert_eq!(
+            serde_json::to_value(&response.processed_client_actions).unwrap(),
+            json!([{"ActionType": "SessionType", "ActionStatus": 1, "Error": ""}])
+        );
+    }
+
+    #[test]
+    fn turns_down_encryption_and_port_forwarding() {
+        let response = respond(&request(json!([
+            {"ActionType": "KMSEncryption", "ActionParameters": {"KMSKeyId": "key"}},
+            {"ActionType": "SessionType", "ActionParameters": {"SessionType": "Port"}},
+        ])));
+
+        let statuses = response
+            .processed_client_actions
+            .iter()
+            .map(|action| action.action_status)
+            .collect::<Vec<_>>();
+        assert_eq!(statuses, [ActionStatus::Failed, ActionStatus::Unsupported]);
+        assert_eq!(response.errors.len(), 2);
+    }
+}
@@ -0,0 +1,12 @@
+mod error;
+
+pub mod handshake;
+pub mod message;
+pub mod session;
+#[cfg(unix)]
+pub mod terminal;
+
+pub use error::Error;
+pub use session::{run, Input};
+#[cfg(unix)]
+pub use terminal::run_in_terminal;
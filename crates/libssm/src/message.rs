@@ -0,0 +1,313 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::Error;
+
+pub const INPUT_STREAM_DATA: &str = "input_stream_data";
+pub const OUTPUT_STREAM_DATA: &str = "output_stream_data";
+pub const ACKNOWLEDGE: &str = "acknowledge";
+pub const CHANNEL_CLOSED: &str = "channel_closed";
+pub const START_PUBLICATION: &str = "start_publication";
+pub const PAUSE_PUBLICATION: &str = "pause_publication";
+
+// every field of the header up to the payload length, which the header
+// length field counts
+const HEADER_LENGTH: usize = 116;
+const MESSAGE_TYPE_LENGTH: usize = 32;
+const SCHEMA_VERSION: u32 = 1;
+// acknowledgements are sent with the SYN and FIN flags set
+pub const ACKNOWLEDGE_FLAGS: u64 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadType {
+    Undefined,
+    Output,
+    Error,
+    Size,
+    Parameter,
+    HandshakeRequest,
+    HandshakeResponse,
+    HandshakeComplete,
+    EncChallengeRequest,
+    EncChallengeResponse,
+    Flag,
+    StdErr,
+    ExitCode,
+    Other(u32),
+}
+
+impl From<u32> for PayloadType {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => PayloadType::Undefined,
+            1 => PayloadType::Output,
+            2 => PayloadType::Error,
+            3 => PayloadType::Size,
+            4 => PayloadType::Parameter,
+            5 => PayloadType::HandshakeRequest,
+            6 => PayloadType::HandshakeResponse,
+            7 => PayloadType::HandshakeComplete,
+            8 => PayloadType::EncChallengeRequest,
+            9 => PayloadType::EncChallengeResponse,
+            10 => PayloadType::Flag,
+            11 => PayloadType::StdErr,
+            12 => PayloadType::ExitCode,
+            value => PayloadType::Other(value),
+        }
+    }
+}
+
+impl From<PayloadType> for u32 {
+    fn from(payload_type: PayloadType) -> Self {
+        match payload_type {
+            PayloadType::Undefined => 0,
+            PayloadType::Output => 1,
+            PayloadType::Error => 2,
+            PayloadType::Size => 3,
+            PayloadType::Parameter => 4,
+            PayloadType::HandshakeRequest => 5,
+            PayloadType::HandshakeResponse => 6,
+            PayloadType::HandshakeComplete => 7,
+            PayloadType::EncChallengeRequest => 8,
+            PayloadType::EncChallengeResponse => 9,
+            PayloadType::Flag => 10,
+            PayloadType::StdErr => 11,
+            PayloadType::ExitCode => 12,
+            PayloadType::Other(value) => value,
+        }
+    }
+}
+
+// binary frame of the data channel, big endian:
+//
+//   0 header length     u32, always 116
+//   4 message type      32 bytes, padded with spaces
+//  36 schema version    u32
+//  40 created date      u64, milliseconds since the epoch
+//  48 sequence number   i64
+//  56 flags             u64
+//  64 message ID        16 bytes, the low half of the UUID first
+//  80 payload digest    SHA-256 of the payload
+// 112 payload type      u32
+// 116 payload length    u32
+// 120 payload
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientMessage {
+    pub message_type: String,
+    pub schema_version: u32,
+    pub created_date: u64,
+    pub sequence_number: i64,
+    pub flags: u64,
+    pub message_id: Uuid,
+    pub payload_type: PayloadType,
+    pub payload: Vec<u8>,
+}
+
+impl ClientMessage {
+    pub fn new(
+        message_type: &str,
+        sequence_number: i64,
+        flags: u64,
+        payload_type: PayloadType,
+        payload: Vec<u8>,
+    ) -> Self {
+        let created_date = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_millis() as u64)
+            .unwrap_or_default();
+
+        Self {
+            message_type: message_type.to_string(),
+            schema_version: SCHEMA_VERSION,
+            created_date,
+            sequence_number,
+            flags,
+            message_id: Uuid::new_v4(),
+            payload_type,
+            payload,
+        }
+    }
+
+    // the agent only moves past a message once it is acknowledged
+    pub fn acknowledge(&self) -> Result<Self, Error> {
+        let content = Acknowledge {
+            acknowledged_message_type: self.message_type.clone(),
+            acknowledged_message_id: self.message_id.to_string(),
+            acknowledged_message_sequence_number: self.sequence_number,
+            is_sequential_message: true,
+        };
+
+        Ok(Self::new(
+            ACKNOWLEDGE,
+            0,
+            ACKNOWLEDGE_FLAGS,
+            PayloadType::Undefined,
+            serde_json::to_vec(&content)?,
+        ))
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut message_type = [b' '; MESSAGE_TYPE_LENGTH];
+        let length = self.message_type.len().min(MESSAGE_TYPE_LENGTH);
+        message_type[..length].copy_from_slice(&self.message_type.as_bytes()[..length]);
+        let id = self.message_id.as_bytes();
+
+        let mut bytes = Vec::with_capacity(HEADER_LENGTH + 4 + self.payload.len());
+        bytes.extend_from_slice(&(HEADER_LENGTH as u32).to_be_bytes());
+        bytes.extend_from_slice(&message_type);
+        bytes.extend_from_slice(&self.schema_version.to_be_bytes());
+        bytes.extend_from_slice(&self.created_date.to_be_bytes());
+        bytes.extend_from_slice(&self.sequence_number.to_be_bytes());
+        bytes.extend_from_slice(&self.flags.to_be_bytes());
+        bytes.extend_from_slice(&id[8..]);
+        bytes.extend_from_slice(&id[..8]);
+        bytes.extend_from_slice(&Sha256::digest(&self.payload));
+        bytes.extend_from_slice(&u32::from(self.payload_type).to_be_bytes());
+        bytes.extend_from_slice(&(self.payload.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&self.payload);
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let header_length = read_u32(bytes, 0)? as usize;
+        if header_length < HEADER_LENGTH {
+            return Err(Error::Protocol(format!(
+                "header of {header_length} bytes, expected {HEADER_LENGTH}"
+            )));
+        }
+        let payload_length = read_u32(bytes, header_length)? as usize;
+        let payload = bytes
+            .get(header_length + 4..header_length + 4 + payload_length)
+            .ok_or_else(|| Error::Protocol(format!("payload of {payload_length} bytes cut off")))?;
+        if Sha256::digest(payload).as_slice() != &bytes[80..112] {
+            return Err(Error::Protocol("payload digest does not match".to_string()));
+        }
+
+        let message_type = String::from_utf8_lossy(&bytes[4..4 + MESSAGE_TYPE_LENGTH])
+            .trim_matches(|c: char| c == ' ' || c == '\0')
+            .to_string();
+        let mut id = [0; 16];
+        id[..8].copy_from_slice(&bytes[72..80]);
+        id[8..].copy_from_slice(&bytes[64..72]);
+
+        Ok(Self {
+            message_type,
+            schema_version: read_u32(bytes, 36)?,
+            created_date: read_u64(bytes, 40)?,
+            sequence_number: read_u64(bytes, 48)? as i64,
+            flags: read_u64(bytes, 56)?,
+            message_id: Uuid::from_bytes(id),
+            payload_type: PayloadType::from(read_u32(bytes, 112)?),
+            payload: payload.to_vec(),
+        })
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, Error> {
+    bytes
+        .get(offset..offset + 4)
+        .and_then(|field| field.try_into().ok())
+        .map(u32::from_be_bytes)
+        .ok_or_else(|| Error::Protocol(format!("frame of {} bytes cut off", bytes.len())))
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Result<u64, Error> {
+    bytes
+        .get(offset..offset + 8)
+        .and_then(|field| field.try_into().ok())
+        .map(u64::from_be_bytes)
+        .ok_or_else(|| Error::Protocol(format!("frame of {} bytes cut off", bytes.len())))
+}
+
+// payload of an `acknowledge` message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Acknowledge {
+    pub acknowledged_message_type: String,
+    pub acknowledged_message_id: String,
+    pub acknowledged_message_sequence_number: i64,
+    pub is_sequential_message: bool,
+}
+
+// payload of a `channel_closed` message, `output` tells why
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ChannelClosed {
+    #[serde(default)]
+    pub session_id: String,
+    #[serde(default)]
+    pub output: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // synthesized from the wire format of the Go plugin, not captured from
+    // an agent, the IDs, dates and payloads are made up
+    const HANDSHAKE_REQUEST: &[u8] = include_bytes!("testdata/handshake_request.bin");
+
+    #[test]
+    fn decodes_handshake_frame() {
+        let message = ClientMessage::decode(HANDSHAKE_REQUEST).unwrap();
+
+        assert_eq!(message.message_type, OUTPUT_STREAM_DATA);
+        assert_eq!(message.schema_version, 1);
+        assert_eq!(message.sequence_number, 0);
+        assert_eq!(
+            message.message_id.to_string(),
+            "6f0a5bc2-8d31-4a5e-9c7f-0e2b4d6a8c10"
+        );
+        assert_eq!(message.payload_type, PayloadType::HandshakeRequest);
+        assert!(String::from_utf8_lossy(&message.payload).contains("InteractiveCommands"));
+    }
+
+    #[test]
+    fn encodes_what_it_decodes() {
+        let message = ClientMessage::decode(HANDSHAKE_REQUEST).unwrap();
+        assert_eq!(message.encode(), HANDSHAKE_REQUEST);
+
+        let input = ClientMessage::new(
+            INPUT_STREAM_DATA,
+            7,
+            0,
+            PayloadType::Output,
+            b"ls\r".to_vec(),
+        );
+        assert_eq!(ClientMessage::decode(&input.encode()).unwrap(), input);
+    }
+
+    #[test]
+    fn rejects_corrupted_frames() {
+        let mut corrupted = HANDSHAKE_REQUEST.to_vec();
+        *corrupted.last_mut().unwrap() ^= 1;
+
+        assert!(matches!(
+            ClientMessage::decode(&corrupted),
+            Err(Error::Protocol(message)) if message.contains("digest")
+        ));
+        assert!(ClientMessage::decode(&HANDSHAKE_REQUEST[..100]).is_err());
+    }
+
+    #[test]
+    fn acknowledges_by_id_and_sequence() {
+        let message = ClientMessage::decode(HANDSHAKE_REQUEST).unwrap();
+        let acknowledge = message.acknowledge().unwrap();
+        let content: Acknowledge = serde_json::from_slice(&acknowledge.payload).unwrap();
+
+        assert_eq!(acknowledge.message_type, ACKNOWLEDGE);
+        assert_eq!(acknowledge.flags, ACKNOWLEDGE_FLAGS);
+        assert_eq!(
+            content,
+            Acknowledge {
+                acknowledged_message_type: OUTPUT_STREAM_DATA.to_string(),
+                acknowledged_message_id: "6f0a5bc2-8d31-4a5e-9c7f-0e2b4d6a8c10".to_string(),
+                acknowledged_message_sequence_number: 0,
+                is_sequential_message: true,
+            }
+        );
+    }
+}
@@ -0,0 +1,432 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use serde_json::json;
+use tokio::{
+    io::{AsyncWrite, AsyncWriteExt},
+    time::{self, MissedTickBehavior},
+};
+use tokio_tungstenite::tungstenite::{self, Message};
+use uuid::Uuid;
+
+use crate::{
+    handshake::{self, HandshakeComplete, HandshakeRequest, CLIENT_VERSION},
+    message::{
+        Acknowledge, ChannelClosed, ClientMessage, PayloadType, ACKNOWLEDGE, CHANNEL_CLOSED,
+        INPUT_STREAM_DATA, OUTPUT_STREAM_DATA, PAUSE_PUBLICATION, START_PUBLICATION,
+    },
+    Error,
+};
+
+// input the agent has not acknowledged is sent again after this long
+pub const RESEND_AFTER: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Input {
+    // keys typed into the terminal
+    Data(Vec<u8>),
+    // terminal size, sent once the handshake completed and on each change
+    Resize { cols: u16, rows: u16 },
+}
+
+// drives a session until the agent closes the channel, with the stream URL
+// and token of a StartSession or ExecuteCommand response, and returns the
+// message it closed the channel with
+pub async fn run<S, I, W>(
+    mut socket: S,
+    token: &str,
+    input: I,
+    mut output: W,
+) -> Result<String, Error>
+where
+    S: Stream<Item = Result<Message, tungstenite::Error>>
+        + Sink<Message, Error = tungstenite::Error>
+        + Unpin,
+    I: Stream<Item = Input> + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let open = json!({
+        "MessageSchemaVersion": "1.0",
+        "RequestId": Uuid::new_v4().to_string(),
+        "TokenValue": token,
+        "ClientId": Uuid::new_v4().to_string(),
+        "ClientVersion": CLIENT_VERSION,
+    });
+    socket.send(Message::Text(open.to_string())).await?;
+
+    let mut channel = Channel::default();
+    let mut input = input.fuse();
+    let mut resend = time::interval(RESEND_AFTER);
+    resend.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            message = socket.next() => match message.transpose()? {
+                Some(Message::Binary(bytes)) => channel.receive(ClientMessage::decode(&bytes)?)?,
+                Some(Message::Close(_)) | None => {
+                    return Err(Error::Protocol(
+                        "the websocket closed before the channel".to_string(),
+                    ))
+                }
+                // pings are answered by the websocket itself
+                Some(_) => {}
+            },
+            Some(input) = input.next() => channel.input(input)?,
+            _ = resend.tick() => channel.resend(Instant::now()),
+        }
+
+        for message in channel.outbox.drain(..) {
+            socket.send(Message::Binary(message.encode())).await?;
+        }
+        if !channel.output.is_empty() {
+            output.write_all(&channel.output).await?;
+            output.flush().await?;
+            channel.output.clear();
+        }
+        if let Some(closed) = channel.closed.take() {
+            let _ = socket.close().await;
+            return Ok(closed);
+        }
+    }
+}
+
+// sequencing of both directions of the data channel, the caller sends the
+// outbox and writes the output after each call
+#[derive(Debug, Default)]
+struct Channel {
+    // sequence number of the next input message
+    sequence: i64,
+    // sequence number of the next output message to show
+    expected: i64,
+    // output that arrived ahead of `expected`
+    early: BTreeMap<i64, ClientMessage>,
+    // input sent and not acknowledged yet, with when it was last sent
+    unacknowledged: VecDeque<(ClientMessage, Instant)>,
+    // keys typed before the handshake completed or while the agent paused
+    // publication
+    queued: VecDeque<Vec<u8>>,
+    size: Option<(u16, u16)>,
+    handshake_complete: bool,
+    paused: bool,
+
+    outbox: Vec<ClientMessage>,
+    output: Vec<u8>,
+    closed: Option<String>,
+}
+
+impl Channel {
+    fn receive(&mut self, message: ClientMessage) -> Result<(), Error> {
+        match message.message_type.as_str() {
+            OUTPUT_STREAM_DATA => {
+                // the agent resends until it sees the acknowledgement, so
+                // duplicates are acknowledged again
+                self.outbox.push(message.acknowledge()?);
+                if message.sequence_number >= self.expected {
+                    self.early.insert(message.sequence_number, message);
+                }
+                while let Some(message) = self.early.remove(&self.expected) {
+                    self.expected += 1;
+                    self.handle(message)?;
+                }
+            }
+            ACKNOWLEDGE => {
+                let acknowledge: Acknowledge = serde_json::from_slice(&message.payload)?;
+                self.unacknowledged.retain(|(message, _)| {
+                    message.message_id.to_string() != acknowledge.acknowledged_message_id
+                });
+            }
+            CHANNEL_CLOSED => {
+                let closed: ChannelClosed = serde_json::from_slice(&message.payload)?;
+                self.closed = Some(closed.output);
+            }
+            START_PUBLICATION => {
+                self.paused = false;
+                self.flush_queued();
+            }
+            PAUSE_PUBLICATION => self.paused = true,
+            // like the plugin, message types it does not know are skipped
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle(&mut self, message: ClientMessage) -> Result<(), Error> {
+        match message.payload_type {
+            PayloadType::Output | PayloadType::StdErr => self.output.extend(message.payload),
+            PayloadType::HandshakeRequest => {
+                let request: HandshakeRequest = serde_json::from_slice(&message.payload)?;
+                let response = handshake::respond(&request);
+                self.send(
+                    PayloadType::HandshakeResponse,
+                    serde_json::to_vec(&response)?,
+                );
+            }
+            PayloadType::HandshakeComplete => {
+                let complete: HandshakeComplete = serde_json::from_slice(&message.payload)?;
+                if !complete.customer_message.is_empty() {
+                    self.output.extend(complete.customer_message.as_bytes());
+                    self.output.extend(b"\r\n");
+                }
+                self.handshake_complete = true;
+                if let Some((cols, rows)) = self.size {
+                    self.send_size(cols, rows)?;
+                }
+                self.flush_queued();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn input(&mut self, input: Input) -> Result<(), Error> {
+        let ready = self.handshake_complete && !self.paused;
+        match input {
+            Input::Data(data) if ready => self.send(PayloadType::Output, data),
+            Input::Data(data) => self.queued.push_back(data),
+            Input::Resize { cols, rows } => {
+                self.size = Some((cols, rows));
+                if ready {
+                    self.send_size(cols, rows)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn send_size(&mut self, cols: u16, rows: u16) -> Result<(), Error> {
+        let size = json!({ "cols": cols, "rows": rows });
+        self.send(PayloadType::Size, serde_json::to_vec(&size)?);
+        Ok(())
+    }
+
+    fn flush_queued(&mut self) {
+        if !self.handshake_complete || self.paused {
+            return;
+        }
+        while let Some(data) = self.queued.pop_front() {
+            self.send(PayloadType::Output, data);
+        }
+    }
+
+    fn send(&mut self, payload_type: PayloadType, payload: Vec<u8>) {
+        let message =
+            ClientMessage::new(INPUT_STREAM_DATA, self.sequence, 0, payload_type, payload);
+        self.sequence += 1;
+        self.unacknowledged
+            .push_back((message.clone(), Instant::now()));
+        self.outbox.push(message);
+    }
+
+    fn resend(&mut self, now: Instant) {
+        for (message, sent_at) in self.unacknowledged.iter_mut() {
+            if now.duration_since(*sent_at) >= RESEND_AFTER {
+                self.outbox.push(message.clone());
+                *sent_at = now;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use tokio::{net::TcpListener, task::JoinHandle};
+    use tokio_tungstenite::{accept_async, connect_async};
+
+    use super::*;
+
+    // frames synthesized from the wire format of the Go plugin, not
+    // captured from an agent, replayed by the stand-in
+    const HANDSHAKE_REQUEST: &[u8] = include_bytes!("testdata/handshake_request.bin");
+    const HANDSHAKE_COMPLETE: &[u8] = include_bytes!("testdata/handshake_complete.bin");
+    const OUTPUT_HELLO: &[u8] = include_bytes!("testdata/output_hello.bin");
+    const OUTPUT_WORLD: &[u8] = include_bytes!("testdata/output_world.bin");
+    const CHANNEL_CLOSED_FRAME: &[u8] = include_bytes!("testdata/channel_closed.bin");
+
+    // what the stand-in saw of the client
+    #[derive(Debug, Default)]
+    struct Seen {
+        open: Value,
+        acknowledged: Vec<i64>,
+        input: Vec<ClientMessage>,
+    }
+
+    // reads client frames, acknowledging its input, until `count` input
+    // messages arrived
+    async fn read_input<S>(socket: &mut S, seen: &mut Seen, count: usize)
+    where
+        S: Stream<Item = Result<Message, tungstenite::Error>>
+            + Sink<Message, Error = tungstenite::Error>
+            + Unpin,
+    {
+        let until = seen.input.len() + count;
+        while seen.input.len() < until {
+            let Some(Ok(Message::Binary(bytes))) = socket.next().await else {
+                panic!("the client closed the socket");
+            };
+            let message = ClientMessage::decode(&bytes).unwrap();
+            match message.message_type.as_str() {
+                ACKNOWLEDGE => {
+                    let acknowledge: Acknowledge =
+                        serde_json::from_slice(&message.payload).unwrap();
+                    seen.acknowledged
+                        .push(acknowledge.acknowledged_message_sequence_number);
+                }
+                INPUT_STREAM_DATA => {
+                    let acknowledge = message.acknowledge().unwrap();
+                    socket
+                        .send(Message::Binary(acknowledge.encode()))
+                        .await
+                        .unwrap();
+                    seen.input.push(message);
+                }
+                message_type => panic!("unexpected {message_type}"),
+            }
+        }
+    }
+
+    // the agent side of a session, replaying the synthesized frames with the
+    // output out of order and once more
+    async fn stand_in() -> (String, JoinHandle<Seen>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let agent = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = accept_async(stream).await.unwrap();
+            let mut seen = Seen::default();
+
+            let Some(Ok(Message::Text(open))) = socket.next().await else {
+                panic!("no OpenDataChannel message");
+            };
+            seen.open = serde_json::from_str(&open).unwrap();
+
+            socket
+                .send(Message::Binary(HANDSHAKE_REQUEST.to_vec()))
+                .await
+                .unwrap();
+            read_input(&mut socket, &mut seen, 1).await;
+            socket
+                .send(Message::Binary(HANDSHAKE_COMPLETE.to_vec()))
+                .await
+                .unwrap();
+            // the size, then the keys typed during the handshake
+            read_input(&mut socket, &mut seen, 2).await;
+
+            for frame in [OUTPUT_WORLD, OUTPUT_HELLO, OUTPUT_HELLO] {
+                socket.send(Message::Binary(frame.to_vec())).await.unwrap();
+            }
+            socket
+                .send(Message::Binary(CHANNEL_CLOSED_FRAME.to_vec()))
+                .await
+                .unwrap();
+            while let Some(Ok(message)) = socket.next().await {
+                if let Message::Binary(bytes) = message {
+                    let message = ClientMessage::decode(&bytes).unwrap();
+                    let acknowledge: Acknowledge =
+                        serde_json::from_slice(&message.payload).unwrap();
+                    seen.acknowledged
+                        .push(acknowledge.acknowledged_message_sequence_number);
+                }
+            }
+            seen
+        });
+
+        (url, agent)
+    }
+
+    #[tokio::test]
+    async fn replays_agent_session() {
+        let (url, agent) = stand_in().await;
+        let (socket, _) = connect_async(&url).await.unwrap();
+        let input = futures::stream::iter([
+            Input::Resize {
+                cols: 120,
+                rows: 40,
+            },
+            Input::Data(b"ls\r".to_vec()),
+        ]);
+        let mut output = vec![];
+
+        let closed = run(socket, "token", input, &mut output).await.unwrap();
+        let seen = agent.await.unwrap();
+
+        assert_eq!(
+            closed,
+            "Exiting session with sessionId: ecs-execute-command-0a1b2c3d4e5f."
+        );
+        assert_eq!(String::from_utf8(output).unwrap(), "hello world\r\n");
+        assert_eq!(seen.open["TokenValue"], "token");
+        assert_eq!(seen.open["MessageSchemaVersion"], "1.0");
+        // every output frame, the duplicate too
+        assert_eq!(seen.acknowledged, [0, 1, 3, 2, 2]);
+
+        let input = seen
+            .input
+            .iter()
+            .map(|message| {
+                (
+                    message.sequence_number,
+                    message.payload_type,
+                    String::from_utf8_lossy(&message.payload).to_string(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(input[0].0, 0);
+        assert_eq!(input[0].1, PayloadType::HandshakeResponse);
+        let response: Value = serde_json::from_str(&input[0].2).unwrap();
+        assert_eq!(response["ProcessedClientActions"][0]["ActionStatus"], 1);
+        assert_eq!(
+            input[1..],
+            [
+                (
+                    1,
+                    PayloadType::Size,
+                    r#"{"cols":120,"rows":40}"#.to_string()
+                ),
+                (2, PayloadType::Output, "ls\r".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn resends_until_acknowledged() {
+        let mut channel = Channel {
+            handshake_complete: true,
+            ..Channel::default()
+        };
+        channel.input(Input::Data(b"a".to_vec())).unwrap();
+        channel.input(Input::Data(b"b".to_vec())).unwrap();
+        let sent = channel.outbox.drain(..).collect::<Vec<_>>();
+
+        let acknowledge = sent[0].acknowledge().unwrap();
+        channel.receive(acknowledge).unwrap();
+        channel.resend(Instant::now());
+        assert!(channel.outbox.is_empty());
+
+        channel.resend(Instant::now() + RESEND_AFTER);
+        assert_eq!(channel.outbox, [sent[1].clone()]);
+    }
+
+    #[test]
+    fn holds_input_while_paused() {
+        let mut channel = Channel {
+            handshake_complete: true,
+            ..Channel::default()
+        };
+        let pause = ClientMessage::new(PAUSE_PUBLICATION, 0, 0, PayloadType::Undefined, vec![]);
+        let start = ClientMessage::new(START_PUBLICATION, 0, 0, PayloadType::Undefined, vec![]);
+
+        channel.receive(pause).unwrap();
+        channel.input(Input::Data(b"a".to_vec())).unwrap();
+        assert!(channel.outbox.is_empty());
+
+        channel.receive(start).unwrap();
+        assert_eq!(channel.outbox.len(), 1);
+        assert_eq!(channel.outbox[0].payload, b"a");
+        assert_eq!(channel.outbox[0].sequence_number, 0);
+    }
+}
@@ -0,0 +1,97 @@
+use std::{
+    io,
+    os::fd::{AsRawFd, RawFd},
+};
+
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, size};
+use futures::stream;
+use tokio::{
+    io::unix::AsyncFd,
+    signal::unix::{signal, SignalKind},
+    sync::mpsc::{self, UnboundedSender},
+};
+use tokio_tungstenite::connect_async;
+
+use crate::{session::run, Error, Input};
+
+// runs the session in the terminal, in raw mode so every key reaches the
+// remote shell, and returns the message the agent closed the channel with
+pub async fn run_in_terminal(stream_url: &str, token: &str) -> Result<String, Error> {
+    let (socket, _) = connect_async(stream_url).await?;
+
+    let (input_tx, mut input_rx) = mpsc::unbounded_channel();
+    let (cols, rows) = size()?;
+    let _ = input_tx.send(Input::Resize { cols, rows });
+
+    enable_raw_mode()?;
+    let reader = tokio::spawn(read_stdin(input_tx.clone()));
+    let resizer = tokio::spawn(watch_size(input_tx));
+
+    let input = stream::poll_fn(move |cx| input_rx.poll_recv(cx));
+    let result = run(socket, token, input, tokio::io::stdout()).await;
+
+    reader.abort();
+    resizer.abort();
+    disable_raw_mode()?;
+    result
+}
+
+struct Stdin;
+
+impl AsRawFd for Stdin {
+    fn as_raw_fd(&self) -> RawFd {
+        libc::STDIN_FILENO
+    }
+}
+
+// stdin stays blocking, it is shared with stdout, so it is only read while
+// poll says a read will not block, and nothing is left reading it once the
+// task is aborted
+async fn read_stdin(input_tx: UnboundedSender<Input>) -> io::Result<()> {
+    let stdin = AsyncFd::new(Stdin)?;
+    let mut buffer = [0; 4096];
+
+    loop {
+        let mut guard = stdin.readable().await?;
+        while readable()? {
+            let read =
+                unsafe { libc::read(libc::STDIN_FILENO, buffer.as_mut_ptr().cast(), buffer.len()) };
+            match read {
+                0 => return Ok(()),
+                read if read < 0 => return Err(io::Error::last_os_error()),
+                read => {
+                    if input_tx
+                        .send(Input::Data(buffer[..read as usize].to_vec()))
+                        .is_err()
+                    {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+        guard.clear_ready();
+    }
+}
+
+fn readable() -> io::Result<bool> {
+    let mut fd = libc::pollfd {
+        fd: libc::STDIN_FILENO,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    match unsafe { libc::poll(&mut fd, 1, 0) } {
+        ready if ready < 0 => Err(io::Error::last_os_error()),
+        ready => Ok(ready > 0),
+    }
+}
+
+async fn watch_size(input_tx: UnboundedSender<Input>) -> io::Result<()> {
+    let mut window_change = signal(SignalKind::window_change())?;
+    while window_change.recv().await.is_some() {
+        let (cols, rows) = size()?;
+        if input_tx.send(Input::Resize { cols, rows }).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
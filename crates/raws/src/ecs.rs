@@ -1,19 +1,67 @@
-use crate::traits::CommandExecute;
+use crate::traits::{CommandExecute, Context};
 
 use async_trait::async_trait;
 use clap::Args;
-use std::io::Result;
+use std::{io::Result, time::Duration};
 
-use libecs::run_app;
+use libecs::{
+    options::{AppOptions, Regions, RoleOptions},
+    run_app,
+};
 
 #[derive(Debug, Args)]
 #[command(version, about, long_about = None)]
-pub struct EcsArgs;
+pub struct EcsArgs {
+    /// Regions to list clusters from, comma separated, or `all` for every
+    /// region enabled in the account
+    #[arg(long, value_delimiter = ',')]
+    regions: Vec<String>,
+
+    /// Role to assume to list the clusters of another account, as
+    /// `<role arn>[,external-id=<id>][,mfa-serial=<arn>]`, repeatable
+    #[arg(long = "role", value_name = "ROLE", value_parser = RoleOptions::parse)]
+    roles: Vec<RoleOptions>,
+
+    /// Seconds between polls of the visible view, 0 turns auto-refresh off
+    #[arg(long = "refresh", value_name = "SECONDS", default_value_t = 5)]
+    refresh_secs: u64,
+
+    /// Scaling a service by more tasks than this asks for a second
+    /// confirmation
+    #[arg(long, value_name = "TASKS", default_value_t = 5)]
+    scale_confirm_above: i32,
+
+    /// Task definition revisions listed in the history of a service
+    #[arg(
+        long,
+        value_name = "COUNT",
+        default_value_t = 10,
+        value_parser = clap::value_parser!(i32).range(1..=100)
+    )]
+    revisions: i32,
+}
 
 #[async_trait]
 impl CommandExecute for EcsArgs {
-    async fn execute(&self) -> Result<()> {
-        run_app().await.unwrap();
-        Ok(())
+    async fn execute(&self, context: &Context) -> Result<()> {
+        let options = AppOptions {
+            profile: context.profile.clone(),
+            regions: Regions::parse(&self.regions),
+            roles: self.roles.clone(),
+            refresh_interval: match self.refresh_secs {
+                0 => None,
+                secs => Some(Duration::from_secs(secs)),
+            },
+            scale_confirm_above: self.scale_confirm_above,
+            revisions: self.revisions,
+        };
+
+        // the report is printed by color_eyre once the terminal is restored
+        run_app(context.config.clone(), options)
+            .await
+            .map_err(|report| {
+                eprintln!("{report:?}");
+                std::io::Error::other("the ecs terminal UI exited with an error")
+            })
     }
 }
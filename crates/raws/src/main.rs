@@ -1,7 +1,8 @@
+use aws_config::{BehaviorVersion, Region, SdkConfig};
 use clap::{Parser, Subcommand};
 use ecs::EcsArgs;
 use ecs_exec_checker::EcsExecChecker;
-use traits::CommandExecute;
+use traits::{CommandExecute, Context};
 
 mod ecs;
 mod ecs_exec_checker;
@@ -10,10 +11,40 @@ mod traits;
 #[derive(Parser)]
 #[command(name = "raws")]
 struct Cli {
+    /// Named profile from the shared AWS config and credentials files
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// AWS region, overrides the region of the profile and environment
+    #[arg(long, global = true)]
+    region: Option<String>,
+
+    /// Endpoint URL used for every AWS service, e.g. a local mock
+    #[arg(long, global = true)]
+    endpoint_url: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+impl Cli {
+    // the flags override the environment, anything left unset falls back to
+    // the default provider chains
+    async fn sdk_config(&self) -> SdkConfig {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest());
+        if let Some(profile) = &self.profile {
+            loader = loader.profile_name(profile);
+        }
+        if let Some(region) = &self.region {
+            loader = loader.region(Region::new(region.clone()));
+        }
+        if let Some(endpoint_url) = &self.endpoint_url {
+            loader = loader.endpoint_url(endpoint_url);
+        }
+        loader.load().await
+    }
+}
+
 #[derive(Debug, Subcommand)]
 enum Commands {
     Ecs(EcsArgs),
@@ -23,9 +54,13 @@ enum Commands {
 #[tokio::main]
 async fn main() -> Result<(), std::io::Error> {
     let cli = Cli::parse();
+    let context = Context {
+        config: cli.sdk_config().await,
+        profile: cli.profile.clone(),
+    };
 
     match cli.command {
-        Commands::Ecs(subcommand) => subcommand.execute().await,
-        Commands::EcsExecChecker(subcommand) => subcommand.execute().await,
+        Commands::Ecs(subcommand) => subcommand.execute(&context).await,
+        Commands::EcsExecChecker(subcommand) => subcommand.execute(&context).await,
     }
 }
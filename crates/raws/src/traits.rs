@@ -1,6 +1,14 @@
 use async_trait::async_trait;
+use aws_config::SdkConfig;
+
+// AWS settings shared by every subcommand, built once from the global flags
+pub struct Context {
+    pub config: SdkConfig,
+    // profile selected with `--profile`, the config does not keep its name
+    pub profile: Option<String>,
+}
 
 #[async_trait]
 pub trait CommandExecute {
-    async fn execute(&self) -> Result<(), std::io::Error>;
+    async fn execute(&self, context: &Context) -> Result<(), std::io::Error>;
 }